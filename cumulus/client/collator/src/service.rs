@@ -26,20 +26,225 @@ use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_consensus::BlockStatus;
 use sp_core::traits::SpawnNamed;
 use sp_runtime::traits::{Block as BlockT, HashingFor, Header as HeaderT, Zero};
+use sp_trie::{CompactProof, StorageProof};
 
 use cumulus_client_consensus_common::ParachainCandidate;
 use polkadot_node_primitives::{
-	BlockData, Collation, CollationSecondedSignal, MaybeCompressedPoV, PoV,
+	BlockData, Collation, CollationSecondedSignal, MaybeCompressedPoV, PoV, POV_BOMB_LIMIT,
 };
+use polkadot_primitives::HeadData;
 
-use codec::Encode;
+use codec::{Decode, Encode};
 use futures::channel::oneshot;
-use parking_lot::Mutex;
-use std::sync::Arc;
+use parking_lot::{Mutex, RwLock};
+use schnellru::{ByLength, LruMap};
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
 /// The logging target.
 const LOG_TARGET: &str = "cumulus-collator";
 
+/// The number of blocks for which [`CollatorService::fetch_collation_info`] caches the result.
+///
+/// A [`CollationInfo`] is bounded by the number of messages a parachain block can send, so the
+/// cache is kept small.
+const COLLATION_INFO_CACHE_SIZE: u32 = 4;
+
+/// The number of recently announced blocks [`CollatorService`] remembers to skip duplicate
+/// announcements.
+const RECENTLY_ANNOUNCED_SIZE: u32 = 16;
+
+/// Time spent in the individual phases of building a collation.
+#[derive(Debug, Default, Clone, Copy)]
+struct CollationBuildTimings {
+	/// Time spent compacting the storage proof.
+	compact_proof: Duration,
+	/// Time spent fetching the [`CollationInfo`] from the runtime.
+	fetch_collation_info: Duration,
+	/// Time spent encoding and compressing the PoV.
+	compress_pov: Duration,
+}
+
+/// Returns the span all events emitted by [`CollatorService`] for the given block are recorded in.
+///
+/// The `correlation_id` field is the hash of the block, so operators can follow a single block
+/// from [`CollatorService::check_block_status`] up to the announcement by filtering on it.
+fn collation_span<H: std::fmt::Debug>(block_hash: &H) -> tracing::Span {
+	tracing::debug_span!(target: LOG_TARGET, "collation", correlation_id = ?block_hash)
+}
+
+/// Error returned by [`compact_proof`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompactProofError<Hash> {
+	/// The proof does not contain the node of the parent state root.
+	///
+	/// This usually means that the block was built on top of a different state than the one of
+	/// the given parent header and needs to be rebuilt on the correct parent.
+	ParentStateMismatch {
+		/// The state root of the parent header.
+		state_root: Hash,
+	},
+	/// The proof contains the parent state root, but misses a node below it.
+	MissingNode {
+		/// The state root of the parent header.
+		state_root: Hash,
+		/// The hash of the first node that was not found in the proof.
+		missing_node: Hash,
+	},
+	/// Compacting the proof failed for any other reason.
+	Other {
+		/// The state root of the parent header.
+		state_root: Hash,
+		/// The underlying error.
+		error: String,
+	},
+}
+
+/// Compact the `proof` recorded while building a block on top of `parent_header`.
+///
+/// Contrary to [`StorageProof::into_compact_proof`], the error distinguishes a proof that does
+/// not belong to the state of `parent_header` from a proof that is merely incomplete.
+pub fn compact_proof<Block: BlockT>(
+	parent_header: &Block::Header,
+	proof: StorageProof,
+) -> Result<CompactProof, CompactProofError<Block::Hash>> {
+	compact_proof_with_root::<Block>(*parent_header.state_root(), proof)
+}
+
+fn compact_proof_with_root<Block: BlockT>(
+	state_root: Block::Hash,
+	proof: StorageProof,
+) -> Result<CompactProof, CompactProofError<Block::Hash>> {
+	proof
+		.into_compact_proof::<HashingFor<Block>>(state_root)
+		.map_err(|error| match error {
+			sp_trie::CompactProofError::TrieError(error) => match *error {
+				sp_trie::trie_types::TrieError::IncompleteDatabase(missing_node)
+					if missing_node == state_root =>
+					CompactProofError::ParentStateMismatch { state_root },
+				sp_trie::trie_types::TrieError::IncompleteDatabase(missing_node) =>
+					CompactProofError::MissingNode { state_root, missing_node },
+				error => CompactProofError::Other { state_root, error: format!("{error:?}") },
+			},
+			error => CompactProofError::Other { state_root, error: format!("{error:?}") },
+		})
+}
+
+/// Compacts the storage proof of a block before it is put into the [`ParachainBlockData`].
+///
+/// [`CollatorService`] uses [`DefaultProofCompactor`] unless another one is set with
+/// [`CollatorService::with_proof_compactor`]. This allows experimenting with other proof formats
+/// without forking the service.
+pub trait ProofCompactor<Block: BlockT>: Send + Sync {
+	/// Compact the `proof` recorded while building a block on top of the state `state_root`.
+	fn compact_proof(
+		&self,
+		proof: StorageProof,
+		state_root: Block::Hash,
+	) -> Result<CompactProof, CompactProofError<Block::Hash>>;
+}
+
+/// The [`ProofCompactor`] that uses [`StorageProof::into_compact_proof`], see [`compact_proof`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultProofCompactor;
+
+impl<Block: BlockT> ProofCompactor<Block> for DefaultProofCompactor {
+	fn compact_proof(
+		&self,
+		proof: StorageProof,
+		state_root: Block::Hash,
+	) -> Result<CompactProof, CompactProofError<Block::Hash>> {
+		compact_proof_with_root::<Block>(state_root, proof)
+	}
+}
+
+/// Error returned by [`CollatorService::try_build_collation`].
+#[derive(Debug)]
+pub enum CollationBuildError<Hash> {
+	/// Compacting the storage proof of the block failed.
+	///
+	/// [`CompactProofError::ParentStateMismatch`] means that the block was built on top of a
+	/// different state than the one of the given parent header and needs to be rebuilt.
+	CompactProof(CompactProofError<Hash>),
+	/// Calling the [`CollectCollationInfo`] runtime api failed.
+	RuntimeApi(sp_api::ApiError),
+	/// The runtime does not implement the [`CollectCollationInfo`] runtime api.
+	CollectCollationInfoUnsupported,
+	/// The collation info reported by the runtime does not belong to the block.
+	InvalidCollationInfo(CollationValidationError<Hash>),
+	/// The runtime reports more upward messages than a [`Collation`] can carry.
+	TooManyUpwardMessages,
+	/// The runtime reports more horizontal messages than a [`Collation`] can carry.
+	TooManyHorizontalMessages,
+	/// The block data contains multiple blocks, which the runtime does not support.
+	MultipleBlocksNotSupported,
+}
+
+/// Error returned by [`CollatorService::validate_collation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollationValidationError<Hash> {
+	/// The head data can not be decoded as a header.
+	InvalidHeadData,
+	/// The head data is the header of a different block.
+	HeadDataMismatch {
+		/// The hash of the block the collation was built for.
+		expected: Hash,
+		/// The hash of the header found in the head data.
+		found: Hash,
+	},
+	/// The horizontal messages are not sorted by recipient or contain more than one message for
+	/// the same recipient.
+	HorizontalMessagesNotSorted,
+	/// The PoV exceeds the maximum size accepted by the relay chain.
+	PoVTooLarge {
+		/// The size of the PoV.
+		size: usize,
+		/// The maximum allowed size.
+		max: usize,
+	},
+}
+
+/// Announce `block_hash` through the current `announce_block` closure, unless it was already
+/// announced recently.
+fn announce_once<Block: BlockT>(
+	recently_announced: &Mutex<LruMap<Block::Hash, ()>>,
+	announce_block: &RwLock<Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>>,
+	block_hash: Block::Hash,
+	data: Option<Vec<u8>>,
+) {
+	{
+		let mut recently_announced = recently_announced.lock();
+		if recently_announced.peek(&block_hash).is_some() {
+			tracing::debug!(target: LOG_TARGET, ?block_hash, "Skipping duplicate announcement.");
+			return
+		}
+		recently_announced.insert(block_hash, ());
+	}
+
+	let announce_block = announce_block.read().clone();
+	announce_block(block_hash, data)
+}
+
+/// Check that `head_data` is the encoded header of the block `block_hash`.
+fn check_head_data<Block: BlockT>(
+	head_data: &HeadData,
+	block_hash: Block::Hash,
+) -> Result<(), CollationValidationError<Block::Hash>> {
+	let header = Block::Header::decode(&mut &head_data.0[..])
+		.map_err(|_| CollationValidationError::InvalidHeadData)?;
+
+	if header.hash() != block_hash {
+		return Err(CollationValidationError::HeadDataMismatch {
+			expected: block_hash,
+			found: header.hash(),
+		})
+	}
+
+	Ok(())
+}
+
 /// Utility functions generally applicable to writing collators for Cumulus.
 pub trait ServiceInterface<Block: BlockT> {
 	/// Checks the status of the given block hash in the Parachain.
@@ -71,6 +276,15 @@ pub trait ServiceInterface<Block: BlockT> {
 
 	/// Directly announce a block on the network.
 	fn announce_block(&self, block_hash: Block::Hash, data: Option<Vec<u8>>);
+
+	/// Returns the number of blocks passed to [`Self::announce_with_barrier`] that are still
+	/// waiting for their signal.
+	///
+	/// A growing number indicates that the relay chain does not second the collations. Returns
+	/// `0` by default, for implementations that do not track their barriers.
+	fn pending_announce_barriers(&self) -> usize {
+		0
+	}
 }
 
 /// The [`CollatorService`] provides common utilities for parachain consensus and authoring.
@@ -81,8 +295,16 @@ pub trait ServiceInterface<Block: BlockT> {
 pub struct CollatorService<Block: BlockT, BS, RA> {
 	block_status: Arc<BS>,
 	wait_to_announce: Arc<Mutex<WaitToAnnounce<Block>>>,
-	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
+	/// The closure is shared by all clones and can be replaced by [`Self::set_announce_block`].
+	/// Callers clone the inner `Arc` under the read lock and call it after releasing the lock, so
+	/// replacing the closure never waits for an announcement in progress.
+	announce_block: Arc<RwLock<Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>>>,
+	/// Announces the blocks passed to [`Self::announce_with_barrier`] once they are seconded.
+	announce_seconded: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 	runtime_api: Arc<RA>,
+	collation_info_cache: Arc<Mutex<LruMap<Block::Hash, (CollationInfo, u32)>>>,
+	recently_announced: Arc<Mutex<LruMap<Block::Hash, ()>>>,
+	proof_compactor: Arc<dyn ProofCompactor<Block>>,
 }
 
 impl<Block: BlockT, BS, RA> Clone for CollatorService<Block, BS, RA> {
@@ -91,7 +313,11 @@ impl<Block: BlockT, BS, RA> Clone for CollatorService<Block, BS, RA> {
 			block_status: self.block_status.clone(),
 			wait_to_announce: self.wait_to_announce.clone(),
 			announce_block: self.announce_block.clone(),
+			announce_seconded: self.announce_seconded.clone(),
 			runtime_api: self.runtime_api.clone(),
+			collation_info_cache: self.collation_info_cache.clone(),
+			recently_announced: self.recently_announced.clone(),
+			proof_compactor: self.proof_compactor.clone(),
 		}
 	}
 }
@@ -113,13 +339,74 @@ where
 		let wait_to_announce =
 			Arc::new(Mutex::new(WaitToAnnounce::new(spawner, announce_block.clone())));
 
-		Self { block_status, wait_to_announce, announce_block, runtime_api }
+		Self::with_wait_to_announce(block_status, wait_to_announce, announce_block, runtime_api)
+	}
+
+	/// Create a new instance that uses the given `wait_to_announce`.
+	///
+	/// Contrary to [`Self::new`], the announcement barrier is not created from a spawner. This
+	/// allows multiple services to share the same barrier state. The announce closure of
+	/// `wait_to_announce` is not used, every service announces the blocks it passed to
+	/// [`Self::announce_with_barrier`] through its own `announce_block`.
+	pub fn with_wait_to_announce(
+		block_status: Arc<BS>,
+		wait_to_announce: Arc<Mutex<WaitToAnnounce<Block>>>,
+		announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
+		runtime_api: Arc<RA>,
+	) -> Self {
+		let announce_block = Arc::new(RwLock::new(announce_block));
+		let recently_announced =
+			Arc::new(Mutex::new(LruMap::new(ByLength::new(RECENTLY_ANNOUNCED_SIZE))));
+
+		// Blocks passed to `announce_with_barrier` are only recorded as announced once they are
+		// seconded, so a barrier that never resolves does not suppress a later announcement.
+		let announce_seconded = {
+			let announce_block = announce_block.clone();
+			let recently_announced = recently_announced.clone();
+			Arc::new(move |block_hash, data| {
+				announce_once::<Block>(&recently_announced, &announce_block, block_hash, data)
+			})
+		};
+
+		Self {
+			block_status,
+			wait_to_announce,
+			announce_block,
+			announce_seconded,
+			runtime_api,
+			collation_info_cache: Arc::new(Mutex::new(LruMap::new(ByLength::new(
+				COLLATION_INFO_CACHE_SIZE,
+			)))),
+			recently_announced,
+			proof_compactor: Arc::new(DefaultProofCompactor),
+		}
+	}
+
+	/// Use the given `proof_compactor` to compact the storage proofs of built collations.
+	pub fn with_proof_compactor(mut self, proof_compactor: Arc<dyn ProofCompactor<Block>>) -> Self {
+		self.proof_compactor = proof_compactor;
+		self
+	}
+
+	/// Replace the closure used to announce blocks.
+	///
+	/// This allows to construct the service before the networking is ready, with a no-op
+	/// closure, and to set the real one later. The closure is updated for all clones of this
+	/// service, including blocks that are already waiting on a barrier of
+	/// [`Self::announce_with_barrier`].
+	pub fn set_announce_block(
+		&self,
+		announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
+	) {
+		*self.announce_block.write() = announce_block;
 	}
 
 	/// Checks the status of the given block hash in the Parachain.
 	///
 	/// Returns `true` if the block could be found and is good to be build on.
 	pub fn check_block_status(&self, hash: Block::Hash, header: &Block::Header) -> bool {
+		let _span = collation_span(&hash).entered();
+
 		match self.block_status.block_status(hash) {
 			Ok(BlockStatus::Queued) => {
 				tracing::debug!(
@@ -179,11 +466,20 @@ where
 	/// Returns `Ok(Some((CollationInfo, ApiVersion)))` on success, `Err(_)` on error or `Ok(None)`
 	/// if the runtime api isn't implemented by the runtime. `ApiVersion` being the version of the
 	/// [`CollectCollationInfo`] runtime api.
+	///
+	/// The result is cached for the last few blocks, so fetching the collation info of the same
+	/// block again does not call into the runtime.
 	pub fn fetch_collation_info(
 		&self,
 		block_hash: Block::Hash,
 		header: &Block::Header,
 	) -> Result<Option<(CollationInfo, u32)>, sp_api::ApiError> {
+		let _span = collation_span(&block_hash).entered();
+
+		if let Some(cached) = self.collation_info_cache.lock().get(&block_hash) {
+			return Ok(Some(cached.clone()))
+		}
+
 		let runtime_api = self.runtime_api.runtime_api();
 
 		let api_version =
@@ -207,45 +503,101 @@ where
 			runtime_api.collect_collation_info(block_hash, header)?
 		};
 
+		self.collation_info_cache
+			.lock()
+			.insert(block_hash, (collation_info.clone(), api_version));
+
 		Ok(Some((collation_info, api_version)))
 	}
 
-	/// Build a full [`Collation`] from a given [`ParachainCandidate`]. This requires
-	/// that the underlying block has been fully imported into the underlying client,
-	/// as it fetches underlying runtime API data.
+	/// Build the [`ParachainBlockData`] for a given [`ParachainCandidate`], without building the
+	/// PoV.
 	///
-	/// This also returns the unencoded parachain block data, in case that is desired.
-	pub fn build_collation(
+	/// This gathers the collation info exactly like [`Self::build_collation`], but neither
+	/// compacts the storage proof nor encodes and compresses the PoV. The block data carries the
+	/// raw proof nodes instead, so it is bigger than the block data of the real collation. Useful
+	/// for dry-run tooling that wants to inspect the block data of a collation that will not be
+	/// submitted.
+	pub fn build_block_data_only(
 		&self,
 		parent_header: &Block::Header,
 		block_hash: Block::Hash,
 		candidate: ParachainCandidate<Block>,
-	) -> Option<(Collation, ParachainBlockData<Block>)> {
+	) -> Result<ParachainBlockData<Block>, CollationBuildError<Block::Hash>> {
+		let _span = collation_span(&block_hash).entered();
+		self.gather_collation_info(
+			parent_header,
+			block_hash,
+			candidate.block.header(),
+			&mut Default::default(),
+		)?;
+
+		let proof = CompactProof { encoded_nodes: candidate.proof.into_iter_nodes().collect() };
+		Ok(ParachainBlockData::<Block>::new(vec![candidate.block], proof))
+	}
+
+	/// Estimate the encoded size of the [`ParachainBlockData`] for the given candidate.
+	///
+	/// The proof is not compacted, instead the size of the block data with the raw proof is
+	/// returned as an upper bound. See [`ParachainBlockData::estimated_encoded_size`].
+	pub fn estimate_block_data_size(&self, candidate: &ParachainCandidate<Block>) -> usize {
+		let proof = CompactProof { encoded_nodes: candidate.proof.iter_nodes().cloned().collect() };
+		ParachainBlockData::<Block>::new(vec![candidate.block.clone()], proof)
+			.estimated_encoded_size()
+	}
+
+	/// Compact the proof and gather the collation info for the given candidate.
+	///
+	/// Returns the collation info, the `api_version` of [`CollectCollationInfo`] that should be
+	/// used to encode the PoV and the parachain block data. The time spent in each phase is
+	/// recorded in `timings`.
+	fn prepare_collation(
+		&self,
+		parent_header: &Block::Header,
+		block_hash: Block::Hash,
+		candidate: ParachainCandidate<Block>,
+		timings: &mut CollationBuildTimings,
+	) -> Result<(CollationInfo, u32, ParachainBlockData<Block>), CollationBuildError<Block::Hash>>
+	{
 		let block = candidate.block;
 
-		let compact_proof = match candidate
-			.proof
-			.into_compact_proof::<HashingFor<Block>>(*parent_header.state_root())
-		{
-			Ok(proof) => proof,
-			Err(e) => {
-				tracing::error!(target: "cumulus-collator", "Failed to compact proof: {:?}", e);
-				return None
-			},
-		};
+		let started = Instant::now();
+		let compact_proof = self
+			.proof_compactor
+			.compact_proof(candidate.proof, *parent_header.state_root())
+			.map_err(CollationBuildError::CompactProof)?;
+		timings.compact_proof = started.elapsed();
 
 		// Create the parachain block data for the validators.
+		let (collation_info, api_version) =
+			self.gather_collation_info(parent_header, block_hash, block.header(), timings)?;
+		let block_data = ParachainBlockData::<Block>::new(vec![block], compact_proof);
+
+		Ok((collation_info, api_version, block_data))
+	}
+
+	/// Gather the collation info of the block `block_hash` with the given `header`.
+	///
+	/// Returns the collation info and the `api_version` of [`CollectCollationInfo`] that should be
+	/// used to encode the PoV. The time spent fetching the collation info is recorded in
+	/// `timings`.
+	fn gather_collation_info(
+		&self,
+		parent_header: &Block::Header,
+		block_hash: Block::Hash,
+		header: &Block::Header,
+		timings: &mut CollationBuildTimings,
+	) -> Result<(CollationInfo, u32), CollationBuildError<Block::Hash>> {
+		let started = Instant::now();
 		let (collation_info, _api_version) = self
-			.fetch_collation_info(block_hash, block.header())
-			.map_err(|e| {
-				tracing::error!(
-					target: LOG_TARGET,
-					error = ?e,
-					"Failed to collect collation info.",
-				)
-			})
-			.ok()
-			.flatten()?;
+			.fetch_collation_info(block_hash, header)
+			.map_err(CollationBuildError::RuntimeApi)?
+			.ok_or(CollationBuildError::CollectCollationInfoUnsupported)?;
+		timings.fetch_collation_info = started.elapsed();
+
+		// The head data reported by the runtime must be the header of the block itself.
+		check_head_data::<Block>(&collation_info.head_data, block_hash)
+			.map_err(CollationBuildError::InvalidCollationInfo)?;
 
 		// Workaround for: https://github.com/paritytech/polkadot-sdk/issues/64
 		//
@@ -258,50 +610,85 @@ where
 			.runtime_api
 			.runtime_api()
 			.api_version::<dyn CollectCollationInfo<Block>>(parent_header.hash())
+			.map_err(CollationBuildError::RuntimeApi)?
+			.ok_or(CollationBuildError::CollectCollationInfoUnsupported)?;
+
+		Ok((collation_info, api_version))
+	}
+
+	/// Build a full [`Collation`] from a given [`ParachainCandidate`]. This requires
+	/// that the underlying block has been fully imported into the underlying client,
+	/// as it fetches underlying runtime API data.
+	///
+	/// This also returns the unencoded parachain block data, in case that is desired.
+	///
+	/// Same as [`Self::try_build_collation`], but logs the error instead of returning it.
+	pub fn build_collation(
+		&self,
+		parent_header: &Block::Header,
+		block_hash: Block::Hash,
+		candidate: ParachainCandidate<Block>,
+	) -> Option<(Collation, ParachainBlockData<Block>)> {
+		self.try_build_collation(parent_header, block_hash, candidate)
+			.map_err(|error| {
+				tracing::error!(
+					target: LOG_TARGET,
+					?block_hash,
+					?error,
+					"Failed to build collation.",
+				)
+			})
 			.ok()
-			.flatten()?;
+	}
 
-		let block_data = ParachainBlockData::<Block>::new(vec![block], compact_proof);
+	/// Build a full [`Collation`] from a given [`ParachainCandidate`].
+	///
+	/// Contrary to [`Self::build_collation`], the reason of a failure is returned. This allows
+	/// the caller to react to [`CompactProofError::ParentStateMismatch`] by rebuilding the block
+	/// on top of the correct parent.
+	///
+	/// The time spent compacting the proof, fetching the collation info and compressing the PoV
+	/// is reported in a debug event, to help find out which phase dominates for large blocks.
+	pub fn try_build_collation(
+		&self,
+		parent_header: &Block::Header,
+		block_hash: Block::Hash,
+		candidate: ParachainCandidate<Block>,
+	) -> Result<(Collation, ParachainBlockData<Block>), CollationBuildError<Block::Hash>> {
+		let _span = collation_span(&block_hash).entered();
+		let mut timings = CollationBuildTimings::default();
+		let (collation_info, api_version, block_data) =
+			self.prepare_collation(parent_header, block_hash, candidate, &mut timings)?;
 
+		let started = Instant::now();
 		let pov = polkadot_node_primitives::maybe_compress_pov(PoV {
 			block_data: BlockData(if api_version >= 3 {
 				block_data.encode()
 			} else {
-				let block_data = block_data.as_v0();
-
-				if block_data.is_none() {
-					tracing::error!(
-						target: LOG_TARGET,
-						"Trying to submit a collation with multiple blocks is not supported by the current runtime."
-					);
-				}
-
-				block_data?.encode()
+				block_data
+					.as_v0()
+					.ok_or(CollationBuildError::MultipleBlocksNotSupported)?
+					.encode()
 			}),
 		});
+		timings.compress_pov = started.elapsed();
+
+		tracing::debug!(
+			target: LOG_TARGET,
+			compact_proof = ?timings.compact_proof,
+			fetch_collation_info = ?timings.fetch_collation_info,
+			compress_pov = ?timings.compress_pov,
+			"Collation build timings.",
+		);
 
 		let upward_messages = collation_info
 			.upward_messages
 			.try_into()
-			.map_err(|e| {
-				tracing::error!(
-					target: LOG_TARGET,
-					error = ?e,
-					"Number of upward messages should not be greater than `MAX_UPWARD_MESSAGE_NUM`",
-				)
-			})
-			.ok()?;
+			.map_err(|_| CollationBuildError::TooManyUpwardMessages)?;
 		let horizontal_messages = collation_info
 			.horizontal_messages
 			.try_into()
-			.map_err(|e| {
-				tracing::error!(
-					target: LOG_TARGET,
-					error = ?e,
-					"Number of horizontal messages should not be greater than `MAX_HORIZONTAL_MESSAGE_NUM`",
-				)
-			})
-			.ok()?;
+			.map_err(|_| CollationBuildError::TooManyHorizontalMessages)?;
 
 		let collation = Collation {
 			upward_messages,
@@ -313,17 +700,77 @@ where
 			proof_of_validity: MaybeCompressedPoV::Compressed(pov),
 		};
 
-		Some((collation, block_data))
+		Ok((collation, block_data))
+	}
+
+	/// Check that the given `collation` for the block `block_hash` is structurally valid.
+	///
+	/// This checks that the head data is the header of `block_hash`, that the horizontal messages
+	/// are sorted by recipient without duplicates and that the compressed PoV does not exceed
+	/// `max_pov_size`, the limit of the relay chain given by
+	/// [`PersistedValidationData::max_pov_size`](polkadot_primitives::PersistedValidationData).
+	/// The number of upward and horizontal messages is already bounded by the types of
+	/// [`Collation`]. Meant to catch a broken collation before it is announced, instead of having
+	/// it rejected by the relay chain.
+	pub fn validate_collation(
+		&self,
+		collation: &Collation,
+		block_hash: Block::Hash,
+		max_pov_size: u32,
+	) -> Result<(), CollationValidationError<Block::Hash>> {
+		check_head_data::<Block>(&collation.head_data, block_hash)?;
+
+		if collation
+			.horizontal_messages
+			.windows(2)
+			.any(|messages| messages[0].recipient >= messages[1].recipient)
+		{
+			return Err(CollationValidationError::HorizontalMessagesNotSorted)
+		}
+
+		let size = match &collation.proof_of_validity {
+			MaybeCompressedPoV::Compressed(pov) => pov.encoded_size(),
+			// The PoV is compressed before it is sent to the relay chain.
+			MaybeCompressedPoV::Raw(pov) =>
+				polkadot_node_primitives::maybe_compress_pov(pov.clone()).encoded_size(),
+		};
+		let max = max_pov_size as usize;
+		if size > max {
+			return Err(CollationValidationError::PoVTooLarge { size, max })
+		}
+
+		Ok(())
+	}
+
+	/// Decode the [`ParachainBlockData`] from the given `pov`.
+	///
+	/// This is the inverse of the encoding done by [`Self::build_collation`]. The `pov` may be
+	/// compressed and may use either the multi block encoding or the legacy single block
+	/// encoding for runtimes with a [`CollectCollationInfo`] version below 3.
+	pub fn decode_block_data(pov: &PoV) -> Result<ParachainBlockData<Block>, codec::Error> {
+		let block_data = sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT)
+			.map_err(|_| codec::Error::from("PoV could not be decompressed"))?;
+
+		ParachainBlockData::<Block>::decode(&mut &block_data[..])
 	}
 
 	/// Inform the networking systems that the block should be announced after an appropriate
 	/// signal has been received. This returns the sending half of the signal.
+	///
+	/// If the block was already announced recently when the signal arrives, it is not announced
+	/// again.
 	pub fn announce_with_barrier(
 		&self,
 		block_hash: Block::Hash,
 	) -> oneshot::Sender<CollationSecondedSignal> {
+		let _span = collation_span(&block_hash).entered();
 		let (result_sender, signed_stmt_recv) = oneshot::channel();
-		self.wait_to_announce.lock().wait_to_announce(block_hash, signed_stmt_recv);
+		tracing::debug!(target: LOG_TARGET, "Announcing block once it is seconded.");
+		self.wait_to_announce.lock().wait_to_announce_with(
+			block_hash,
+			signed_stmt_recv,
+			self.announce_seconded.clone(),
+		);
 		result_sender
 	}
 }
@@ -356,6 +803,535 @@ where
 	}
 
 	fn announce_block(&self, block_hash: Block::Hash, data: Option<Vec<u8>>) {
-		(self.announce_block)(block_hash, data)
+		let _span = collation_span(&block_hash).entered();
+		tracing::debug!(target: LOG_TARGET, "Announcing block.");
+		announce_once::<Block>(&self.recently_announced, &self.announce_block, block_hash, data)
+	}
+
+	fn pending_announce_barriers(&self) -> usize {
+		self.wait_to_announce.lock().pending().len()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cumulus_test_client::{
+		runtime::{Block, Hash, Header},
+		Client, DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+	};
+	use futures::{executor::block_on, future::BoxFuture};
+	use polkadot_node_primitives::{SignedFullStatement, Statement};
+	use polkadot_primitives::{Hash as PHash, SigningContext, ValidatorId};
+	use polkadot_primitives_test_helpers::dummy_committed_candidate_receipt_v2;
+	use sp_api::ApiRef;
+	use sp_keystore::{testing::MemoryKeystore, Keystore, KeystorePtr};
+	use sp_runtime::{traits::BlakeTwo256, RuntimeAppPublic};
+	use sp_tracing::{
+		test_log_capture::init_log_capture,
+		tracing::{subscriber, Level},
+	};
+	use sp_trie::{LayoutV1, MemoryDB, TrieDBMutBuilder, TrieMut};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	/// Runtime api mock that counts the calls to [`CollectCollationInfo`].
+	#[derive(Clone, Default)]
+	struct TestApi {
+		collect_collation_info_calls: Arc<AtomicUsize>,
+		/// The head data to report instead of the encoded header of the block.
+		head_data: Option<HeadData>,
+	}
+
+	struct RuntimeApi {
+		inner: TestApi,
+	}
+
+	impl ProvideRuntimeApi<Block> for TestApi {
+		type Api = RuntimeApi;
+
+		fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+			RuntimeApi { inner: self.clone() }.into()
+		}
+	}
+
+	sp_api::mock_impl_runtime_apis! {
+		impl CollectCollationInfo<Block> for RuntimeApi {
+			fn collect_collation_info(&self, header: &Header) -> CollationInfo {
+				self.inner.collect_collation_info_calls.fetch_add(1, Ordering::Relaxed);
+
+				CollationInfo {
+					upward_messages: Vec::new(),
+					horizontal_messages: Vec::new(),
+					new_validation_code: None,
+					processed_downward_messages: 0,
+					hrmp_watermark: 0,
+					head_data: self
+						.inner
+						.head_data
+						.clone()
+						.unwrap_or_else(|| header.encode().into()),
+				}
+			}
+		}
+	}
+
+	/// Spawner that keeps the spawned tasks, so tests can drive them deterministically.
+	#[derive(Clone, Default)]
+	struct TestSpawner(Arc<Mutex<Vec<BoxFuture<'static, ()>>>>);
+
+	impl SpawnNamed for TestSpawner {
+		fn spawn_blocking(
+			&self,
+			_: &'static str,
+			_: Option<&'static str>,
+			future: BoxFuture<'static, ()>,
+		) {
+			self.0.lock().push(future);
+		}
+
+		fn spawn(&self, _: &'static str, _: Option<&'static str>, future: BoxFuture<'static, ()>) {
+			self.0.lock().push(future);
+		}
+	}
+
+	impl TestSpawner {
+		/// Runs all tasks spawned so far to completion.
+		fn run_spawned(&self) {
+			let spawned = std::mem::take(&mut *self.0.lock());
+			spawned.into_iter().for_each(block_on);
+		}
+	}
+
+	type AnnounceBlock = Arc<dyn Fn(Hash, Option<Vec<u8>>) + Send + Sync>;
+
+	fn test_service(
+		api: TestApi,
+		announce_block: AnnounceBlock,
+	) -> (CollatorService<Block, Client, TestApi>, TestSpawner) {
+		let spawner = TestSpawner::default();
+		let service = CollatorService::new(
+			Arc::new(TestClientBuilder::new().build()),
+			Arc::new(spawner.clone()),
+			announce_block,
+			Arc::new(api),
+		);
+
+		(service, spawner)
+	}
+
+	/// Returns a closure that records the announced blocks.
+	fn recording_announce_block() -> (AnnounceBlock, Arc<Mutex<Vec<Hash>>>) {
+		let announced = Arc::new(Mutex::new(Vec::new()));
+		let announce_block = {
+			let announced = announced.clone();
+			Arc::new(move |hash, _| announced.lock().push(hash))
+		};
+
+		(announce_block, announced)
+	}
+
+	/// Returns a signal that the collation was seconded by a validator.
+	fn seconded_signal() -> CollationSecondedSignal {
+		let keystore: KeystorePtr = Arc::new(MemoryKeystore::new());
+		let public = Keystore::sr25519_generate_new(&*keystore, ValidatorId::ID, None).unwrap();
+		let relay_parent = PHash::repeat_byte(1);
+		let signing_context = SigningContext { parent_hash: relay_parent, session_index: 0 };
+
+		let statement = SignedFullStatement::sign(
+			&keystore,
+			Statement::Seconded(dummy_committed_candidate_receipt_v2(relay_parent)),
+			&signing_context,
+			0.into(),
+			&public.into(),
+		)
+		.ok()
+		.flatten()
+		.expect("Signing statement");
+
+		CollationSecondedSignal { statement, relay_parent }
+	}
+
+	/// Returns a parent header and a candidate built on top of it.
+	///
+	/// The proof of the candidate contains the whole state of the parent.
+	fn build_candidate() -> (Header, ParachainCandidate<Block>) {
+		let mut db = MemoryDB::<BlakeTwo256>::default();
+		let mut state_root = Default::default();
+		{
+			let mut trie =
+				TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut state_root).build();
+			for key in 0u32..16 {
+				trie.insert(&key.to_le_bytes(), &[key as u8; 40]).unwrap();
+			}
+		}
+		let proof = StorageProof::new(db.drain().into_values().map(|(node, _)| node));
+
+		let parent =
+			Header::new(1, Default::default(), state_root, Default::default(), Default::default());
+		let header = Header::new(
+			2,
+			Default::default(),
+			Default::default(),
+			parent.hash(),
+			Default::default(),
+		);
+
+		(parent, ParachainCandidate { block: Block::new(header, Vec::new()), proof })
+	}
+
+	#[test]
+	fn proof_against_wrong_parent_state_is_reported() {
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let (mut parent, candidate) = build_candidate();
+		let block_hash = candidate.block.hash();
+		parent.state_root = Hash::repeat_byte(1);
+
+		match service.try_build_collation(&parent, block_hash, candidate) {
+			Err(CollationBuildError::CompactProof(CompactProofError::ParentStateMismatch {
+				state_root,
+			})) => assert_eq!(state_root, parent.state_root),
+			res => panic!("Unexpected result: {:?}", res.map(|_| ())),
+		}
+	}
+
+	#[test]
+	fn head_data_not_matching_the_block_is_rejected() {
+		let (parent, candidate) = build_candidate();
+		let block_hash = candidate.block.hash();
+
+		let api = TestApi { head_data: Some(parent.encode().into()), ..Default::default() };
+		let (service, _) = test_service(api, Arc::new(|_, _| {}));
+		match service.try_build_collation(&parent, block_hash, candidate) {
+			Err(CollationBuildError::InvalidCollationInfo(
+				CollationValidationError::HeadDataMismatch { expected, found },
+			)) => {
+				assert_eq!(expected, block_hash);
+				assert_eq!(found, parent.hash());
+			},
+			res => panic!("Unexpected result: {:?}", res.map(|_| ())),
+		}
+
+		let (parent, candidate) = build_candidate();
+		let api = TestApi { head_data: Some(HeadData(vec![1, 2, 3])), ..Default::default() };
+		let (service, _) = test_service(api, Arc::new(|_, _| {}));
+		assert!(matches!(
+			service.try_build_collation(&parent, block_hash, candidate),
+			Err(CollationBuildError::InvalidCollationInfo(
+				CollationValidationError::InvalidHeadData
+			)),
+		));
+	}
+
+	#[test]
+	fn validate_collation_works() {
+		use polkadot_primitives::{Id as ParaId, OutboundHrmpMessage, MAX_POV_SIZE};
+
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let (parent, candidate) = build_candidate();
+		let block_hash = candidate.block.hash();
+		let (collation, _) = service.try_build_collation(&parent, block_hash, candidate).unwrap();
+
+		assert_eq!(service.validate_collation(&collation, block_hash, MAX_POV_SIZE), Ok(()));
+
+		let mut invalid = collation.clone();
+		invalid.head_data = HeadData(vec![1, 2, 3]);
+		assert_eq!(
+			service.validate_collation(&invalid, block_hash, MAX_POV_SIZE),
+			Err(CollationValidationError::InvalidHeadData),
+		);
+
+		let mut invalid = collation.clone();
+		invalid.head_data = parent.encode().into();
+		assert_eq!(
+			service.validate_collation(&invalid, block_hash, MAX_POV_SIZE),
+			Err(CollationValidationError::HeadDataMismatch {
+				expected: block_hash,
+				found: parent.hash(),
+			}),
+		);
+
+		let message = |recipient: u32| OutboundHrmpMessage {
+			recipient: ParaId::from(recipient),
+			data: vec![],
+		};
+		for messages in [vec![message(2), message(1)], vec![message(1), message(1)]] {
+			let mut invalid = collation.clone();
+			invalid.horizontal_messages = messages.try_into().unwrap();
+			assert_eq!(
+				service.validate_collation(&invalid, block_hash, MAX_POV_SIZE),
+				Err(CollationValidationError::HorizontalMessagesNotSorted),
+			);
+		}
+
+		let MaybeCompressedPoV::Compressed(ref pov) = collation.proof_of_validity else {
+			panic!("Built collations contain a compressed PoV")
+		};
+		let size = pov.encoded_size();
+		assert_eq!(
+			service.validate_collation(&collation, block_hash, size as u32 - 1),
+			Err(CollationValidationError::PoVTooLarge { size, max: size - 1 }),
+		);
+	}
+
+	#[test]
+	fn blocks_are_announced_only_once() {
+		let (announce_block, announced) = recording_announce_block();
+		let (service, spawner) = test_service(TestApi::default(), announce_block);
+		let hash = Hash::repeat_byte(1);
+
+		service.announce_block(hash, None);
+		service.announce_block(hash, None);
+		assert_eq!(*announced.lock(), vec![hash]);
+
+		// A block that was announced directly is not announced again once it is seconded.
+		assert!(service.announce_with_barrier(hash).send(seconded_signal()).is_ok());
+		spawner.run_spawned();
+		assert_eq!(*announced.lock(), vec![hash]);
+
+		// A block that is seconded multiple times is only announced once.
+		let other = Hash::repeat_byte(2);
+		assert!(service.announce_with_barrier(other).send(seconded_signal()).is_ok());
+		assert!(service.announce_with_barrier(other).send(seconded_signal()).is_ok());
+		spawner.run_spawned();
+		assert_eq!(*announced.lock(), vec![hash, other]);
+
+		service.announce_block(other, None);
+		assert_eq!(*announced.lock(), vec![hash, other]);
+	}
+
+	#[test]
+	fn unresolved_barrier_does_not_suppress_announcement() {
+		let (announce_block, announced) = recording_announce_block();
+		let (service, spawner) = test_service(TestApi::default(), announce_block);
+		let hash = Hash::repeat_byte(1);
+
+		let sender = service.announce_with_barrier(hash);
+		assert_eq!(service.pending_announce_barriers(), 1);
+
+		service.announce_block(hash, None);
+		assert_eq!(*announced.lock(), vec![hash]);
+
+		drop(sender);
+		spawner.run_spawned();
+		assert_eq!(*announced.lock(), vec![hash]);
+		assert_eq!(service.pending_announce_barriers(), 0);
+	}
+
+	#[test]
+	fn build_block_data_only_contains_the_candidate() {
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let (parent, candidate) = build_candidate();
+		let block = candidate.block.clone();
+		let block_hash = block.header().hash();
+
+		let block_data = service.build_block_data_only(&parent, block_hash, candidate).unwrap();
+
+		assert_eq!(block_data.blocks(), &[block]);
+		assert!(!block_data.proof().encoded_nodes.is_empty());
+	}
+
+	#[test]
+	fn block_data_size_estimate_is_an_upper_bound() {
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let (parent, candidate) = build_candidate();
+		let block_hash = candidate.block.header().hash();
+
+		let estimate = service.estimate_block_data_size(&candidate);
+		let (_, block_data) = service.try_build_collation(&parent, block_hash, candidate).unwrap();
+		assert!(
+			estimate >= block_data.encoded_size(),
+			"{estimate} < {}",
+			block_data.encoded_size()
+		);
+	}
+
+	#[test]
+	fn collation_logs_carry_the_correlation_id() {
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let (parent, candidate) = build_candidate();
+		let block_hash = candidate.block.header().hash();
+
+		let (log_capture, subscriber) = init_log_capture(Level::DEBUG, false);
+		subscriber::with_default(subscriber, || {
+			service.try_build_collation(&parent, block_hash, candidate).unwrap();
+			service.announce_block(block_hash, None);
+		});
+
+		let logs = log_capture.get_logs();
+		let correlation_id = format!("correlation_id={block_hash:?}");
+		for message in ["Collation build timings.", "Announcing block."] {
+			let line = logs.lines().find(|line| line.contains(message)).unwrap();
+			assert!(line.contains(&correlation_id), "{line}");
+		}
+	}
+
+	#[test]
+	fn services_can_share_the_announcement_barrier() {
+		let spawner = TestSpawner::default();
+		let wait_to_announce = Arc::new(Mutex::new(WaitToAnnounce::new(
+			Arc::new(spawner.clone()),
+			Arc::new(|_, _| panic!("Every service announces through its own closure")),
+		)));
+		let service = |announce_block: AnnounceBlock| {
+			CollatorService::<Block, _, _>::with_wait_to_announce(
+				Arc::new(TestClientBuilder::new().build()),
+				wait_to_announce.clone(),
+				announce_block,
+				Arc::new(TestApi::default()),
+			)
+		};
+		let (announce_block, first_announced) = recording_announce_block();
+		let first = service(announce_block);
+		let (announce_block, second_announced) = recording_announce_block();
+		let second = service(announce_block);
+		let hashes = [1, 2, 3].map(Hash::repeat_byte);
+
+		let senders = [
+			first.announce_with_barrier(hashes[0]),
+			second.announce_with_barrier(hashes[1]),
+			first.clone().announce_with_barrier(hashes[2]),
+		];
+		assert_eq!(first.pending_announce_barriers(), 3);
+		assert_eq!(second.pending_announce_barriers(), 3);
+
+		// Creating the second service does not redirect the barriers of the first one.
+		for sender in senders {
+			assert!(sender.send(seconded_signal()).is_ok());
+		}
+		spawner.run_spawned();
+		assert_eq!(*first_announced.lock(), vec![hashes[0], hashes[2]]);
+		assert_eq!(*second_announced.lock(), vec![hashes[1]]);
+		assert_eq!(first.pending_announce_barriers(), 0);
+		assert_eq!(second.pending_announce_barriers(), 0);
+	}
+
+	#[test]
+	fn collation_info_is_cached() {
+		let api = TestApi::default();
+		let (service, _) = test_service(api.clone(), Arc::new(|_, _| {}));
+		let (parent, candidate) = build_candidate();
+		let header = candidate.block.header().clone();
+		let block_hash = header.hash();
+
+		let (collation_info, api_version) =
+			service.fetch_collation_info(block_hash, &header).unwrap().unwrap();
+		assert_eq!(api.collect_collation_info_calls.load(Ordering::Relaxed), 1);
+
+		let (cached, cached_version) =
+			service.fetch_collation_info(block_hash, &header).unwrap().unwrap();
+		assert_eq!(api.collect_collation_info_calls.load(Ordering::Relaxed), 1);
+		assert_eq!(cached, collation_info);
+		assert_eq!(cached_version, api_version);
+
+		// Building the collation reuses the cached collation info.
+		service.try_build_collation(&parent, block_hash, candidate).unwrap();
+		assert_eq!(api.collect_collation_info_calls.load(Ordering::Relaxed), 1);
+
+		service.fetch_collation_info(parent.hash(), &parent).unwrap().unwrap();
+		assert_eq!(api.collect_collation_info_calls.load(Ordering::Relaxed), 2);
+	}
+
+	#[test]
+	fn decode_block_data_round_trips() {
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let (parent, candidate) = build_candidate();
+		let block_hash = candidate.block.header().hash();
+
+		let (collation, block_data) =
+			service.try_build_collation(&parent, block_hash, candidate).unwrap();
+		let MaybeCompressedPoV::Compressed(pov) = collation.proof_of_validity else {
+			panic!("The PoV is compressed")
+		};
+
+		let decoded = CollatorService::<Block, Client, TestApi>::decode_block_data(&pov).unwrap();
+		assert_eq!(decoded.encode(), block_data.encode());
+
+		let invalid = PoV { block_data: BlockData(vec![1, 2, 3]) };
+		assert!(CollatorService::<Block, Client, TestApi>::decode_block_data(&invalid).is_err());
+	}
+
+	#[test]
+	fn announce_block_can_be_replaced() {
+		let (service, spawner) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let first = Hash::repeat_byte(1);
+		let second = Hash::repeat_byte(2);
+
+		let sender = service.announce_with_barrier(second);
+		service.announce_block(first, None);
+
+		let (announce_block, announced) = recording_announce_block();
+		service.clone().set_announce_block(announce_block);
+		assert!(announced.lock().is_empty());
+
+		// Blocks that were already waiting on a barrier use the new closure as well.
+		assert!(sender.send(seconded_signal()).is_ok());
+		spawner.run_spawned();
+		assert_eq!(*announced.lock(), vec![second]);
+
+		let third = Hash::repeat_byte(3);
+		service.announce_block(third, None);
+		assert_eq!(*announced.lock(), vec![second, third]);
+	}
+
+	#[test]
+	fn collation_build_timings_are_logged() {
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let (parent, candidate) = build_candidate();
+		let block_hash = candidate.block.header().hash();
+
+		let (log_capture, subscriber) = init_log_capture(Level::DEBUG, false);
+		subscriber::with_default(subscriber, || {
+			service.try_build_collation(&parent, block_hash, candidate).unwrap();
+		});
+
+		let logs = log_capture.get_logs();
+		let line = logs.lines().find(|line| line.contains("Collation build timings.")).unwrap();
+		for field in ["compact_proof=", "fetch_collation_info=", "compress_pov="] {
+			assert!(line.contains(field), "{line}");
+		}
+	}
+
+	/// Proof compactor that counts its calls and forwards to [`DefaultProofCompactor`].
+	#[derive(Default)]
+	struct CountingProofCompactor {
+		calls: AtomicUsize,
+	}
+
+	impl ProofCompactor<Block> for CountingProofCompactor {
+		fn compact_proof(
+			&self,
+			proof: StorageProof,
+			state_root: Hash,
+		) -> Result<CompactProof, CompactProofError<Hash>> {
+			self.calls.fetch_add(1, Ordering::Relaxed);
+			ProofCompactor::<Block>::compact_proof(&DefaultProofCompactor, proof, state_root)
+		}
+	}
+
+	#[test]
+	fn custom_proof_compactor_is_used() {
+		let compactor = Arc::new(CountingProofCompactor::default());
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let service = service.with_proof_compactor(compactor.clone());
+
+		let (parent, candidate) = build_candidate();
+		let block_hash = candidate.block.header().hash();
+		service.try_build_collation(&parent, block_hash, candidate).unwrap();
+		assert_eq!(compactor.calls.load(Ordering::Relaxed), 1);
+	}
+
+	#[test]
+	fn build_block_data_only_does_not_compact_the_proof() {
+		let compactor = Arc::new(CountingProofCompactor::default());
+		let (service, _) = test_service(TestApi::default(), Arc::new(|_, _| {}));
+		let service = service.with_proof_compactor(compactor.clone());
+
+		let (parent, candidate) = build_candidate();
+		let block_hash = candidate.block.header().hash();
+		let nodes = candidate.proof.clone().into_iter_nodes().collect::<Vec<_>>();
+
+		let block_data = service.build_block_data_only(&parent, block_hash, candidate).unwrap();
+		assert_eq!(compactor.calls.load(Ordering::Relaxed), 0);
+		assert_eq!(block_data.proof().encoded_nodes, nodes);
 	}
 }
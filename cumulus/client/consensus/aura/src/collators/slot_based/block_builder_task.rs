@@ -574,7 +574,16 @@ pub(crate) async fn determine_core<H: HeaderT, RI: RelayChainInterface + 'static
 		}
 	};
 
-	let core_info = CumulusDigestItem::find_core_info(para_parent.digest());
+	let Ok(claim_queue_offset) = relay_parent_offset.try_into().map(ClaimQueueOffset) else {
+		tracing::debug!(
+			target: LOG_TARGET,
+			relay_parent_offset,
+			"Relay parent offset does not fit into a claim queue offset",
+		);
+		return Ok(None)
+	};
+	let core_info =
+		CumulusDigestItem::find_core_info_for_offset(para_parent.digest(), claim_queue_offset);
 
 	// If we are using a new relay parent, we can start over from the start.
 	let (selector, core_index) = if is_new_relay_parent {
@@ -601,7 +610,7 @@ pub(crate) async fn determine_core<H: HeaderT, RI: RelayChainInterface + 'static
 	Ok(Some(Core {
 		selector: CoreSelector(selector as u8),
 		core_index,
-		claim_queue_offset: ClaimQueueOffset(relay_parent_offset as u8),
+		claim_queue_offset,
 		number_of_cores: cores_at_offset.len() as u16,
 	}))
 }
@@ -187,6 +187,72 @@ async fn determine_core_with_core_info() {
 	}
 }
 
+#[tokio::test]
+async fn determine_core_uses_core_info_of_relay_parent_offset() {
+	let (headers, best_hash) = create_header_chain();
+	let client = TestRelayClient::new(headers);
+	let mut cache = RelayChainDataCache::new(client, 1.into());
+
+	let relay_parent = RelayHeader {
+		parent_hash: best_hash,
+		number: 101,
+		state_root: Default::default(),
+		extrinsics_root: Default::default(),
+		digest: Default::default(),
+	};
+
+	// The para parent selected core 0 at offset 0 and core 1 at offset 1.
+	let mut digest = sp_runtime::generic::Digest::default();
+	for offset in [0, 1] {
+		let core_info = CoreInfo {
+			selector: CoreSelector(offset),
+			claim_queue_offset: ClaimQueueOffset(offset),
+			number_of_cores: 3.into(),
+		};
+		digest.push(CumulusDigestItem::CoreInfo(core_info).to_digest_item());
+	}
+	digest.push(cumulus_primitives_core::rpsr_digest::relay_parent_storage_root_item(
+		*relay_parent.state_root(),
+		*relay_parent.number(),
+	));
+	let para_parent = TestHeader {
+		parent_hash: best_hash.into(),
+		number: 1,
+		state_root: Default::default(),
+		extrinsics_root: Default::default(),
+		digest,
+	};
+
+	// Every core is claimed by the para at both offsets.
+	let claim_queue = (0..3)
+		.map(|core| (CoreIndex(core), [ParaId::from(1), ParaId::from(1)].into()))
+		.collect::<BTreeMap<_, VecDeque<_>>>();
+	cache.insert_test_data(
+		relay_parent.hash(),
+		RelayChainData {
+			relay_parent_header: relay_parent.clone(),
+			claim_queue: ClaimQueueSnapshot::from(claim_queue),
+			max_pov_size: 1024 * 1024,
+			last_claimed_core_selector: None,
+		},
+	);
+
+	for offset in [0, 1] {
+		let core = determine_core(&mut cache, &relay_parent, 1.into(), &para_parent, offset)
+			.await
+			.unwrap()
+			.unwrap();
+		// The next selector after the one of the core info at `offset`.
+		assert_eq!(core.core_selector(), CoreSelector(offset as u8 + 1));
+		assert_eq!(core.core_index(), CoreIndex(offset + 1));
+		assert_eq!(core.total_cores(), 3);
+	}
+
+	// An offset that can not be a claim queue offset never finds a core.
+	let result = determine_core(&mut cache, &relay_parent, 1.into(), &para_parent, 256).await;
+	assert!(matches!(result, Ok(None)));
+}
+
 #[tokio::test]
 async fn determine_core_no_cores_available() {
 	let (headers, _best_hash) = create_header_chain();
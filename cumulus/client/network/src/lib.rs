@@ -39,6 +39,7 @@ use polkadot_primitives::{
 
 use codec::{Decode, DecodeAll, Encode};
 use futures::{channel::oneshot, future::FutureExt, Future};
+use parking_lot::Mutex;
 use std::{fmt, marker::PhantomData, pin::Pin, sync::Arc};
 
 #[cfg(test)]
@@ -424,6 +425,7 @@ where
 pub struct WaitToAnnounce<Block: BlockT> {
 	spawner: Arc<dyn SpawnNamed + Send + Sync>,
 	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
+	pending: Arc<Mutex<Vec<Block::Hash>>>,
 }
 
 impl<Block: BlockT> WaitToAnnounce<Block> {
@@ -432,7 +434,15 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 		spawner: Arc<dyn SpawnNamed + Send + Sync>,
 		announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 	) -> WaitToAnnounce<Block> {
-		WaitToAnnounce { spawner, announce_block }
+		WaitToAnnounce { spawner, announce_block, pending: Default::default() }
+	}
+
+	/// Returns the hashes of the blocks that are still waiting for their candidate message.
+	///
+	/// A block stops being pending once it was announced, or once the sender of its candidate
+	/// message was dropped.
+	pub fn pending(&self) -> Vec<Block::Hash> {
+		self.pending.lock().clone()
 	}
 
 	/// Wait for a candidate message for the block, then announce the block. The candidate
@@ -443,6 +453,22 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 		signed_stmt_recv: oneshot::Receiver<CollationSecondedSignal>,
 	) {
 		let announce_block = self.announce_block.clone();
+		self.wait_to_announce_with(block_hash, signed_stmt_recv, announce_block)
+	}
+
+	/// Same as [`Self::wait_to_announce`], but announces the block through the given
+	/// `announce_block` closure instead of the one `self` was created with.
+	///
+	/// This allows multiple users to share one `WaitToAnnounce`, while each of them announces its
+	/// blocks through its own closure.
+	pub fn wait_to_announce_with(
+		&mut self,
+		block_hash: <Block as BlockT>::Hash,
+		signed_stmt_recv: oneshot::Receiver<CollationSecondedSignal>,
+		announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
+	) {
+		let pending = self.pending.clone();
+		pending.lock().push(block_hash);
 
 		self.spawner.spawn(
 			"cumulus-wait-to-announce",
@@ -455,6 +481,11 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 
 				wait_to_announce::<Block>(block_hash, announce_block, signed_stmt_recv).await;
 
+				let mut pending = pending.lock();
+				if let Some(index) = pending.iter().position(|hash| *hash == block_hash) {
+					pending.swap_remove(index);
+				}
+
 				tracing::debug!(
 					target: "cumulus-network",
 					"block announcement finished",
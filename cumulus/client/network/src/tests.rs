@@ -681,6 +681,55 @@ fn block_announced_without_statement_and_block_only_backed(#[case] runtime_versi
 	});
 }
 
+/// Spawner that keeps the spawned tasks, so tests can drive them deterministically.
+#[derive(Clone, Default)]
+struct TestSpawner(Arc<Mutex<Vec<futures::future::BoxFuture<'static, ()>>>>);
+
+impl sp_core::traits::SpawnNamed for TestSpawner {
+	fn spawn_blocking(
+		&self,
+		_: &'static str,
+		_: Option<&'static str>,
+		future: futures::future::BoxFuture<'static, ()>,
+	) {
+		self.0.lock().push(future);
+	}
+
+	fn spawn(
+		&self,
+		_: &'static str,
+		_: Option<&'static str>,
+		future: futures::future::BoxFuture<'static, ()>,
+	) {
+		self.0.lock().push(future);
+	}
+}
+
+#[test]
+fn wait_to_announce_tracks_pending_blocks() {
+	let spawner = TestSpawner::default();
+	let mut wait_to_announce =
+		WaitToAnnounce::<Block>::new(Arc::new(spawner.clone()), Arc::new(|_, _| {}));
+
+	let (sender1, receiver1) = oneshot::channel();
+	let (_sender2, receiver2) = oneshot::channel();
+	wait_to_announce.wait_to_announce(H256::repeat_byte(1), receiver1);
+	wait_to_announce.wait_to_announce(H256::repeat_byte(2), receiver2);
+	assert_eq!(wait_to_announce.pending().len(), 2);
+
+	let mut tasks = std::mem::take(&mut *spawner.0.lock());
+	assert_eq!(tasks.len(), 2);
+
+	// Resolving the barrier of the first block removes it from the pending blocks.
+	drop(sender1);
+	block_on(tasks.remove(0));
+	assert_eq!(wait_to_announce.pending(), vec![H256::repeat_byte(2)]);
+
+	// The second block keeps waiting for its barrier.
+	assert!(block_on(async { poll!(&mut tasks[0]) }).is_pending());
+	assert_eq!(wait_to_announce.pending(), vec![H256::repeat_byte(2)]);
+}
+
 #[derive(Default)]
 struct ApiData {
 	validators: Vec<ValidatorId>,
@@ -1509,12 +1509,16 @@ impl<T: Config> Pallet<T> {
 
 	/// Send the ump signals
 	fn send_ump_signal() {
-		use cumulus_primitives_core::relay_chain::{UMPSignal, UMP_SEPARATOR};
+		use cumulus_primitives_core::{
+			relay_chain::{UMPSignal, UMP_SEPARATOR},
+			ClaimQueueOffset,
+		};
 
 		UpwardMessages::<T>::mutate(|up| {
-			if let Some(core_info) =
-				CumulusDigestItem::find_core_info(&frame_system::Pallet::<T>::digest())
-			{
+			if let Some(core_info) = CumulusDigestItem::find_core_info_for_offset(
+				&frame_system::Pallet::<T>::digest(),
+				ClaimQueueOffset(T::RelayParentOffset::get() as u8),
+			) {
 				up.push(UMP_SEPARATOR);
 
 				// Send the core selector signal.
@@ -38,7 +38,6 @@ use frame_support::{
 	weights::{Weight, WeightMeter},
 };
 use frame_system::{limits::BlockWeights, pallet_prelude::BlockNumberFor, RawOrigin};
-use sp_core::ConstU32;
 use sp_runtime::{traits::BlakeTwo256, BuildStorage};
 use sp_version::RuntimeVersion;
 use std::cell::RefCell;
@@ -84,6 +83,7 @@ impl frame_system::Config for Test {
 
 parameter_types! {
 	pub const RelayOrigin: AggregateMessageOrigin = AggregateMessageOrigin::Parent;
+	pub static RelayParentOffset: u32 = 0;
 }
 
 impl Config for Test {
@@ -98,7 +98,7 @@ impl Config for Test {
 	type CheckAssociatedRelayNumber = AnyRelayNumber;
 	type ConsensusHook = TestConsensusHook;
 	type WeightInfo = ();
-	type RelayParentOffset = ConstU32<0>;
+	type RelayParentOffset = RelayParentOffset;
 }
 
 std::thread_local! {
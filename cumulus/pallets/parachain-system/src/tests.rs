@@ -1655,3 +1655,39 @@ fn ump_fee_factor_increases_and_decreases() {
 			},
 		);
 }
+
+#[test]
+fn ump_signal_selects_the_core_info_of_the_relay_parent_offset() {
+	use cumulus_primitives_core::{
+		relay_chain::{UMPSignal, UMP_SEPARATOR},
+		ClaimQueueOffset, CoreInfo, CoreSelector,
+	};
+
+	let core_infos = [0, 1].map(|offset| CoreInfo {
+		selector: CoreSelector(offset + 1),
+		claim_queue_offset: ClaimQueueOffset(offset),
+		number_of_cores: 2.into(),
+	});
+
+	for core_info in &core_infos {
+		RelayParentOffset::set(core_info.claim_queue_offset.0 as u32);
+
+		new_test_ext().execute_with(|| {
+			for core_info in &core_infos {
+				System::deposit_log(
+					CumulusDigestItem::CoreInfo(core_info.clone()).to_digest_item(),
+				);
+			}
+
+			ParachainSystem::send_ump_signal();
+			assert_eq!(
+				UpwardMessages::<Test>::get(),
+				vec![
+					UMP_SEPARATOR,
+					UMPSignal::SelectCore(core_info.selector, core_info.claim_queue_offset)
+						.encode(),
+				]
+			);
+		});
+	}
+}
@@ -839,6 +839,11 @@ impl pallet_revive::Config for Runtime {
 	type FeeInfo = pallet_revive::evm::fees::Info<Address, Signature, EthExtraImpl>;
 	type MaxEthExtrinsicWeight = MaxEthExtrinsicWeight;
 	type DebugEnabled = ConstBool<false>;
+	type EvmOpcodePolicy = frame_support::traits::GetDefault;
+	type EvmMemoryLimit = ConstU32<{ pallet_revive::limits::EVM_MEMORY_BYTES }>;
+	type EvmReturnDataLimit = ConstU32<{ pallet_revive::limits::CALLDATA_BYTES }>;
+	type EvmDifficulty = frame_support::traits::GetDefault;
+	type EvmRandomness = pallet_revive::NoRandomness;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -958,6 +963,7 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		}
 	}
 
+	#[api_version(7)]
 	impl sp_block_builder::BlockBuilder<Block> for Runtime {
 		fn apply_extrinsic(extrinsic: <Block as BlockT>::Extrinsic) -> ApplyExtrinsicResult {
 			Executive::apply_extrinsic(extrinsic)
@@ -977,6 +983,45 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		) -> sp_inherents::CheckInherentsResult {
 			data.check_extrinsics(&block)
 		}
+
+		fn apply_inherents(
+			data: sp_inherents::InherentData,
+		) -> Result<
+			Vec<(<Block as BlockT>::Extrinsic, ApplyExtrinsicResult)>,
+			sp_inherents::CheckInherentsResult,
+		> {
+			Executive::apply_inherents(
+				data,
+				|data| data.create_extrinsics(),
+				|data, block| data.check_extrinsics(block),
+			)
+		}
+
+		fn dry_run_extrinsics(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+		) -> Vec<ApplyExtrinsicResult> {
+			Executive::dry_run_extrinsics(extrinsics)
+		}
+
+		fn generate_and_check_inherents(
+			data: sp_inherents::InherentData,
+		) -> (Vec<<Block as BlockT>::Extrinsic>, sp_inherents::CheckInherentsResult) {
+			Executive::generate_and_check_inherents(
+				data,
+				|data| data.create_extrinsics(),
+				|data, block| data.check_extrinsics(block),
+			)
+		}
+
+		fn apply_extrinsic_with_weight_report(
+			extrinsic: <Block as BlockT>::Extrinsic,
+		) -> (ApplyExtrinsicResult, frame_support::weights::WeightReport) {
+			Executive::apply_extrinsic_with_weight_report(extrinsic)
+		}
+
+		fn try_apply_extrinsic(extrinsic: Vec<u8>) -> ApplyExtrinsicResult {
+			Executive::try_apply_extrinsic(&extrinsic)
+		}
 	}
 
 	impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
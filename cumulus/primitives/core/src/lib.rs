@@ -274,7 +274,9 @@ impl CumulusDigestItem {
 
 	/// Find [`CumulusDigestItem::CoreInfo`] in the given `digest`.
 	///
-	/// If there are multiple valid digests, this returns the value of the first one.
+	/// If there are multiple valid digests, this returns the value of the first one. Reading a
+	/// single entry is sufficient for valid blocks, as `parachain-system` rejects blocks that
+	/// carry more than one (see [`Self::core_info_exists_at_max_once`]).
 	pub fn find_core_info(digest: &Digest) -> Option<CoreInfo> {
 		digest.convert_first(|d| match d {
 			DigestItem::PreRuntime(id, val) if id == &CUMULUS_CONSENSUS_ID => {
@@ -290,6 +292,38 @@ impl CumulusDigestItem {
 		})
 	}
 
+	/// Find the first [`CumulusDigestItem::CoreInfo`] in the given `digest` that was selected at
+	/// the given `claim_queue_offset`.
+	///
+	/// Chains authoring against an offset relay parent select their core at a claim queue offset
+	/// equal to their relay parent offset, so this should be used with that offset to avoid
+	/// picking up a core info for a different offset.
+	pub fn find_core_info_for_offset(
+		digest: &Digest,
+		claim_queue_offset: ClaimQueueOffset,
+	) -> Option<CoreInfo> {
+		Self::core_info_iter(digest)
+			.find(|core_info| core_info.claim_queue_offset == claim_queue_offset)
+	}
+
+	/// Returns all [`CumulusDigestItem::CoreInfo`] entries in the given `digest`, in the order
+	/// they appear.
+	pub fn all_core_info(digest: &Digest) -> Vec<CoreInfo> {
+		Self::core_info_iter(digest).collect()
+	}
+
+	/// Iterates over all [`CumulusDigestItem::CoreInfo`] entries in the given `digest`.
+	fn core_info_iter(digest: &Digest) -> impl Iterator<Item = CoreInfo> + '_ {
+		digest.logs().iter().filter_map(|d| match d {
+			DigestItem::PreRuntime(id, val) if id == &CUMULUS_CONSENSUS_ID =>
+				match CumulusDigestItem::decode_all(&mut &val[..]) {
+					Ok(CumulusDigestItem::CoreInfo(core_info)) => Some(core_info),
+					_ => None,
+				},
+			_ => None,
+		})
+	}
+
 	/// Returns the found [`CoreInfo`] and iff [`Self::CoreInfo`] exists at max once in the given
 	/// `digest`.
 	pub fn core_info_exists_at_max_once(digest: &Digest) -> CoreInfoExistsAtMaxOnce {
@@ -559,6 +593,69 @@ sp_api::decl_runtime_apis! {
 mod tests {
 	use super::*;
 
+	fn core_info(selector: u8, offset: u8, cores: u16) -> CoreInfo {
+		CoreInfo {
+			selector: CoreSelector(selector),
+			claim_queue_offset: ClaimQueueOffset(offset),
+			number_of_cores: cores.into(),
+		}
+	}
+
+	#[test]
+	fn all_core_info_returns_every_entry() {
+		let first = core_info(0, 0, 1);
+		let second = core_info(1, 1, 2);
+
+		let mut digest = Digest::default();
+		digest.push(CumulusDigestItem::CoreInfo(first.clone()).to_digest_item());
+		digest.push(CumulusDigestItem::RelayParent(Default::default()).to_digest_item());
+		digest.push(CumulusDigestItem::CoreInfo(second.clone()).to_digest_item());
+
+		assert_eq!(CumulusDigestItem::all_core_info(&digest), vec![first.clone(), second]);
+		assert_eq!(CumulusDigestItem::find_core_info(&digest), Some(first));
+		assert_eq!(
+			CumulusDigestItem::core_info_exists_at_max_once(&digest),
+			CoreInfoExistsAtMaxOnce::MoreThanOnce
+		);
+		assert!(CumulusDigestItem::all_core_info(&Digest::default()).is_empty());
+	}
+
+	#[test]
+	fn find_core_info_for_offset_selects_matching_offset() {
+		let offset_0 = core_info(0, 0, 1);
+		let offset_1 = core_info(2, 1, 3);
+
+		let mut digest = Digest::default();
+		digest.push(CumulusDigestItem::CoreInfo(offset_0.clone()).to_digest_item());
+		digest.push(CumulusDigestItem::CoreInfo(offset_1.clone()).to_digest_item());
+
+		assert_eq!(
+			CumulusDigestItem::find_core_info_for_offset(&digest, ClaimQueueOffset(0)),
+			Some(offset_0.clone())
+		);
+		assert_eq!(
+			CumulusDigestItem::find_core_info_for_offset(&digest, ClaimQueueOffset(1)),
+			Some(offset_1.clone())
+		);
+		assert_eq!(
+			CumulusDigestItem::find_core_info_for_offset(&digest, ClaimQueueOffset(2)),
+			None
+		);
+
+		let mut digest = Digest::default();
+		digest.push(CumulusDigestItem::CoreInfo(offset_1.clone()).to_digest_item());
+		digest.push(CumulusDigestItem::CoreInfo(offset_0.clone()).to_digest_item());
+
+		assert_eq!(
+			CumulusDigestItem::find_core_info_for_offset(&digest, ClaimQueueOffset(0)),
+			Some(offset_0)
+		);
+		assert_eq!(
+			CumulusDigestItem::find_core_info_for_offset(&digest, ClaimQueueOffset(1)),
+			Some(offset_1)
+		);
+	}
+
 	#[test]
 	fn one_block_using_one_core_works() {
 		let schedule = NextSlotSchedule::one_block_using_one_core();
@@ -160,6 +160,25 @@ impl<Block> ParachainBlockData<Block> {
 	}
 }
 
+impl<Block: Encode> ParachainBlockData<Block> {
+	/// Returns the encoded size of `self`, without encoding it as a whole.
+	///
+	/// If the proof of `self` holds the nodes of a raw [`StorageProof`](sp_trie::StorageProof),
+	/// this is an upper bound for the encoded size with the compacted proof. Compacting only omits
+	/// the hashes of child nodes that are part of the proof, so the compact proof is never bigger
+	/// than the raw one.
+	pub fn estimated_encoded_size(&self) -> usize {
+		match self {
+			Self::V0 { block, proof } => block[0].encoded_size() + proof.encoded_size(),
+			Self::V1 { blocks, proof } => {
+				// `V1` is prefixed by its version.
+				let version_size = VERSIONED_PARACHAIN_BLOCK_DATA_PREFIX.len() + 1;
+				version_size + blocks.encoded_size() + proof.encoded_size()
+			},
+		}
+	}
+}
+
 impl<Block: BlockT> ParachainBlockData<Block> {
 	/// Log the size of the individual components (header, extrinsics, storage proof) as info.
 	pub fn log_size_info(&self) {
@@ -238,6 +257,57 @@ mod tests {
 		assert_eq!(decoded, v0);
 	}
 
+	#[test]
+	fn estimated_encoded_size_is_exact_for_both_versions() {
+		let block = TestBlock::new(
+			Header::new_from_number(10),
+			vec![TestExtrinsic::new_bare(MockCallU64(10))],
+		);
+		let proof = CompactProof { encoded_nodes: vec![vec![10u8; 200], vec![20u8; 30]] };
+
+		let v0 = ParachainBlockData::V0 { block: [block.clone()], proof: proof.clone() };
+		assert_eq!(v0.estimated_encoded_size(), v0.encode().len());
+
+		let v1 = ParachainBlockData::new(vec![block.clone(), block], proof);
+		assert_eq!(v1.estimated_encoded_size(), v1.encode().len());
+	}
+
+	#[test]
+	fn estimated_encoded_size_of_the_raw_proof_is_an_upper_bound() {
+		use sp_runtime::traits::BlakeTwo256;
+		use sp_trie::{LayoutV1, MemoryDB, StorageProof, TrieDBMutBuilder, TrieMut};
+
+		for num_blocks in [1u64, 2, 4, 8] {
+			let mut db = MemoryDB::<BlakeTwo256>::default();
+			let mut root = Default::default();
+			{
+				let mut trie =
+					TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut root).build();
+				for key in 0..num_blocks * 10 {
+					trie.insert(&key.to_le_bytes(), &[key as u8; 40]).unwrap();
+				}
+			}
+			let proof = StorageProof::new(db.drain().into_values().map(|(node, _)| node));
+
+			let blocks = (0..num_blocks)
+				.map(|n| {
+					TestBlock::new(
+						Header::new_from_number(n),
+						vec![TestExtrinsic::new_bare(MockCallU64(n))],
+					)
+				})
+				.collect::<Vec<_>>();
+
+			let raw_proof = CompactProof { encoded_nodes: proof.iter_nodes().cloned().collect() };
+			let estimate =
+				ParachainBlockData::new(blocks.clone(), raw_proof).estimated_encoded_size();
+			let compact_proof = proof.into_compact_proof::<BlakeTwo256>(root).unwrap();
+			let real = ParachainBlockData::new(blocks, compact_proof).encode().len();
+
+			assert!(estimate >= real, "{estimate} < {real} for {num_blocks} blocks");
+		}
+	}
+
 	#[test]
 	fn decoding_encoding_v1_works() {
 		let v1 = ParachainBlockData::<TestBlock>::V1 {
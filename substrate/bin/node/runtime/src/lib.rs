@@ -1539,6 +1539,11 @@ impl pallet_revive::Config for Runtime {
 	type FeeInfo = pallet_revive::evm::fees::Info<Address, Signature, EthExtraImpl>;
 	type MaxEthExtrinsicWeight = MaxEthExtrinsicWeight;
 	type DebugEnabled = ConstBool<false>;
+	type EvmOpcodePolicy = frame_support::traits::GetDefault;
+	type EvmMemoryLimit = ConstU32<{ pallet_revive::limits::EVM_MEMORY_BYTES }>;
+	type EvmReturnDataLimit = ConstU32<{ pallet_revive::limits::CALLDATA_BYTES }>;
+	type EvmDifficulty = frame_support::traits::GetDefault;
+	type EvmRandomness = RandomnessCollectiveFlip;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -26,7 +26,7 @@
 
 #![warn(missing_docs)]
 
-use codec::Encode;
+use codec::{DecodeAll, Encode};
 
 use sp_api::{
 	ApiExt, ApiRef, CallApiAt, Core, ProofRecorder, ProvideRuntimeApi, StorageChanges,
@@ -37,11 +37,12 @@ use sp_core::traits::CallContext;
 use sp_runtime::{
 	legacy,
 	traits::{Block as BlockT, Hash, HashingFor, Header as HeaderT, NumberFor, One},
-	Digest, ExtrinsicInclusionMode,
+	transaction_validity::InvalidTransaction,
+	ApplyExtrinsicResult, Digest, ExtrinsicInclusionMode, Weight,
 };
-use std::marker::PhantomData;
+use std::{marker::PhantomData, time::Instant};
 
-pub use sp_block_builder::BlockBuilder as BlockBuilderApi;
+pub use sp_block_builder::{BlockBuilder as BlockBuilderApi, BlockBuildingTimings};
 use sp_trie::proof_size_extension::ProofSizeExt;
 
 /// A builder for creating an instance of [`BlockBuilder`].
@@ -190,6 +191,8 @@ pub struct BuiltBlock<Block: BlockT> {
 	pub storage_changes: StorageChanges<Block>,
 	/// An optional proof that was recorded while building the block.
 	pub proof: Option<StorageProof>,
+	/// The time spent in the individual phases of building the block.
+	pub timings: BlockBuildingTimings,
 }
 
 impl<Block: BlockT> BuiltBlock<Block> {
@@ -199,6 +202,29 @@ impl<Block: BlockT> BuiltBlock<Block> {
 	}
 }
 
+/// The order in which [`BlockBuilder::optimize_inclusion`] tries to include the candidates.
+///
+/// Candidates that are equal according to the strategy are ordered by the hash of their encoded
+/// extrinsic, so the order does not depend on the order the candidates were given in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InclusionStrategy {
+	/// Try the candidates paying the highest fee first.
+	GreedyByFee,
+	/// Try the candidates paying the highest fee per `ref_time` first.
+	GreedyByFeePerWeight,
+}
+
+/// An extrinsic that could be included into the block.
+#[derive(Debug, Clone)]
+pub struct InclusionCandidate<Extrinsic> {
+	/// The extrinsic itself.
+	pub extrinsic: Extrinsic,
+	/// The fee paid by the extrinsic when it is included.
+	pub fee: u128,
+	/// The weight of the extrinsic.
+	pub weight: Weight,
+}
+
 /// Utility for building new (valid) blocks from a stream of extrinsics.
 pub struct BlockBuilder<'a, Block: BlockT, C: ProvideRuntimeApi<Block> + 'a> {
 	extrinsics: Vec<Block::Extrinsic>,
@@ -210,6 +236,7 @@ pub struct BlockBuilder<'a, Block: BlockT, C: ProvideRuntimeApi<Block> + 'a> {
 	/// The estimated size of the block header.
 	estimated_header_size: usize,
 	extrinsic_inclusion_mode: ExtrinsicInclusionMode,
+	timings: BlockBuildingTimings,
 }
 
 impl<'a, Block, C> BlockBuilder<'a, Block, C>
@@ -273,6 +300,7 @@ where
 			estimated_header_size,
 			call_api_at,
 			extrinsic_inclusion_mode,
+			timings: Default::default(),
 		})
 	}
 
@@ -285,20 +313,37 @@ where
 	///
 	/// This will ensure the extrinsic can be validly executed (by executing it).
 	pub fn push(&mut self, xt: <Block as BlockT>::Extrinsic) -> Result<(), Error> {
-		let parent_hash = self.parent_hash;
-		let extrinsics = &mut self.extrinsics;
-		let version = self.version;
+		push_extrinsic(
+			&*self.api,
+			self.parent_hash,
+			self.version,
+			&mut self.extrinsics,
+			&mut self.timings,
+			xt,
+		)
+	}
 
-		self.api.execute_in_transaction(|api| {
-			let res = if version < 6 {
-				#[allow(deprecated)]
-				api.apply_extrinsic_before_version_6(parent_hash, xt.clone())
-					.map(legacy::byte_sized_error::convert_to_latest)
-			} else {
-				api.apply_extrinsic(parent_hash, xt.clone())
-			};
+	/// Push a SCALE encoded extrinsic onto the block's list of extrinsics.
+	///
+	/// Same as [`Self::push`], but an extrinsic that fails to decode is rejected as
+	/// [`InvalidTransaction::Call`] instead of failing the runtime call, so the caller can skip it
+	/// and continue with the next one. The extrinsic is decoded once on the node side. With
+	/// version 7 of the [`BlockBuilderApi`], `encoded` is then passed to the runtime as is instead
+	/// of encoding the decoded extrinsic again.
+	pub fn push_encoded(&mut self, encoded: Vec<u8>) -> Result<(), Error> {
+		let xt = Block::Extrinsic::decode_all(&mut &encoded[..]).map_err(|_| {
+			Error::from(ApplyExtrinsicFailed::Validity(InvalidTransaction::Call.into()))
+		})?;
+
+		if self.version < 7 {
+			return self.push(xt)
+		}
 
-			match res {
+		let parent_hash = self.parent_hash;
+		let extrinsics = &mut self.extrinsics;
+		let started = Instant::now();
+		let res = self.api.execute_in_transaction(|api| {
+			match api.try_apply_extrinsic(parent_hash, encoded) {
 				Ok(Ok(_)) => {
 					extrinsics.push(xt);
 					TransactionOutcome::Commit(Ok(()))
@@ -308,16 +353,134 @@ where
 				)),
 				Err(e) => TransactionOutcome::Rollback(Err(Error::from(e))),
 			}
+		});
+
+		self.timings.extrinsics += started.elapsed();
+		res
+	}
+
+	/// Run `f` on top of the current state of the block and keep or discard its changes.
+	///
+	/// If `f` returns [`TransactionOutcome::Rollback`], all extrinsics pushed through the given
+	/// [`Checkpoint`] are removed again and all their storage changes are discarded, including the
+	/// extrinsic index tracked by the runtime. Taking a checkpoint is cheap, it only opens a new
+	/// storage transaction instead of cloning the overlay.
+	pub fn with_checkpoint<R>(
+		&mut self,
+		f: impl FnOnce(&mut Checkpoint<'_, Block, C::Api>) -> TransactionOutcome<R>,
+	) -> R {
+		let parent_hash = self.parent_hash;
+		let version = self.version;
+		let extrinsics = &mut self.extrinsics;
+		let timings = &mut self.timings;
+		let num_extrinsics = extrinsics.len();
+
+		self.api.execute_in_transaction(|api| {
+			let outcome = f(&mut Checkpoint {
+				api,
+				parent_hash,
+				version,
+				extrinsics: &mut *extrinsics,
+				timings,
+			});
+
+			if let TransactionOutcome::Rollback(_) = outcome {
+				extrinsics.truncate(num_extrinsics);
+			}
+
+			outcome
 		})
 	}
 
+	/// Find the order of `candidates` that includes the most fees.
+	///
+	/// At most `max_orderings` distinct orders are tried on top of the current state of the
+	/// block, in this sequence: the order given by `strategy`, the order of `candidates` itself
+	/// and then the order given by `strategy` with one pair of neighbours swapped, starting at the
+	/// front. The search is not exhaustive, as the number of orders grows factorially with the
+	/// number of candidates. Candidates that fail to apply, e.g. because the block is full or they
+	/// conflict with a previously applied candidate, are skipped. Every order is tried in a
+	/// checkpoint that is rolled back, so the block is not changed.
+	///
+	/// Returns the extrinsics that were included by the order with the highest total fee, in the
+	/// order they should be pushed. If multiple orders include the same fee, the one that was
+	/// tried first is preferred. Together with the tiebreak documented on [`InclusionStrategy`],
+	/// this makes the result independent of the order of `candidates` whenever the strategy order
+	/// is at least as good, so independently built blocks from the same pool match.
+	pub fn optimize_inclusion(
+		&mut self,
+		candidates: Vec<InclusionCandidate<Block::Extrinsic>>,
+		strategy: InclusionStrategy,
+		max_orderings: usize,
+	) -> Vec<Block::Extrinsic> {
+		let mut ordered = candidates
+			.iter()
+			.enumerate()
+			.map(|(index, candidate)| (HashingFor::<Block>::hash_of(&candidate.extrinsic), index))
+			.collect::<Vec<_>>();
+		ordered.sort_by(|(a_hash, a), (b_hash, b)| {
+			let (a, b) = (&candidates[*a], &candidates[*b]);
+			let by_strategy = match strategy {
+				InclusionStrategy::GreedyByFee => b.fee.cmp(&a.fee),
+				InclusionStrategy::GreedyByFeePerWeight => {
+					// Compare `fee / ref_time` without losing precision.
+					let a_weight = a.weight.ref_time().max(1) as u128;
+					let b_weight = b.weight.ref_time().max(1) as u128;
+					b.fee.saturating_mul(a_weight).cmp(&a.fee.saturating_mul(b_weight))
+				},
+			};
+			by_strategy.then_with(|| a_hash.cmp(b_hash))
+		});
+		let ordered = ordered.into_iter().map(|(_, index)| index).collect::<Vec<_>>();
+
+		let swapped = (1..ordered.len()).map(|index| {
+			let mut order = ordered.clone();
+			order.swap(index - 1, index);
+			order
+		});
+		let mut orders = Vec::<Vec<usize>>::new();
+		for order in [ordered.clone(), (0..candidates.len()).collect()].into_iter().chain(swapped) {
+			if orders.len() >= max_orderings {
+				break
+			}
+			if !orders.contains(&order) {
+				orders.push(order);
+			}
+		}
+
+		let mut best: Option<(u128, Vec<Block::Extrinsic>)> = None;
+		for order in orders {
+			let (fee, included) = self.with_checkpoint(|checkpoint| {
+				let mut fee = 0u128;
+				let mut included = Vec::new();
+
+				for candidate in order.into_iter().map(|index| &candidates[index]) {
+					if checkpoint.push(candidate.extrinsic.clone()).is_ok() {
+						fee = fee.saturating_add(candidate.fee);
+						included.push(candidate.extrinsic.clone());
+					}
+				}
+
+				TransactionOutcome::Rollback((fee, included))
+			});
+
+			if best.as_ref().map_or(true, |(best_fee, _)| fee > *best_fee) {
+				best = Some((fee, included));
+			}
+		}
+
+		best.map(|(_, included)| included).unwrap_or_default()
+	}
+
 	/// Consume the builder to build a valid `Block` containing all pushed extrinsics.
 	///
 	/// Returns the build `Block`, the changes to the storage and an optional `StorageProof`
 	/// supplied by `self.api`, combined as [`BuiltBlock`].
 	/// The storage proof will be `Some(_)` when proof recording was enabled.
 	pub fn build(mut self) -> Result<BuiltBlock<Block>, Error> {
+		let started = Instant::now();
 		let header = self.api.finalize_block(self.parent_hash)?;
+		self.timings.finalize = started.elapsed();
 
 		debug_assert_eq!(
 			header.extrinsics_root().clone(),
@@ -340,6 +503,7 @@ where
 			block: <Block as BlockT>::new(header, self.extrinsics),
 			storage_changes,
 			proof,
+			timings: self.timings,
 		})
 	}
 
@@ -351,13 +515,67 @@ where
 		inherent_data: sp_inherents::InherentData,
 	) -> Result<Vec<Block::Extrinsic>, Error> {
 		let parent_hash = self.parent_hash;
-		self.api
+		let started = Instant::now();
+		let inherents = self
+			.api
 			.execute_in_transaction(move |api| {
 				// `create_inherents` should not change any state, to ensure this we always rollback
 				// the transaction.
 				TransactionOutcome::Rollback(api.inherent_extrinsics(parent_hash, inherent_data))
 			})
-			.map_err(|e| Error::Application(Box::new(e)))
+			.map_err(|e| Error::Application(Box::new(e)));
+
+		self.timings.inherents += started.elapsed();
+		inherents
+	}
+
+	/// Create the inherents for the block and push them in a single runtime call.
+	///
+	/// The runtime checks the created inherents before applying any of them. If this check fails,
+	/// nothing is pushed and the returned
+	/// [`CheckInherentsResult`](sp_inherents::CheckInherentsResult) contains the errors. Otherwise
+	/// each inherent is returned together with the result of applying it and the inherents that
+	/// applied successfully are pushed onto the block.
+	///
+	/// Requires version 7 of the [`BlockBuilderApi`].
+	pub fn apply_inherents(
+		&mut self,
+		inherent_data: sp_inherents::InherentData,
+	) -> Result<
+		Result<Vec<(Block::Extrinsic, ApplyExtrinsicResult)>, sp_inherents::CheckInherentsResult>,
+		Error,
+	> {
+		if self.version < 7 {
+			return Err(Error::VersionInvalid("Unsupported BlockBuilderApi version".to_string()))
+		}
+
+		let parent_hash = self.parent_hash;
+		let extrinsics = &mut self.extrinsics;
+		let started = Instant::now();
+		let applied = self.api.execute_in_transaction(|api| {
+			match api.apply_inherents(parent_hash, inherent_data) {
+				Ok(applied) => {
+					if let Ok(applied) = &applied {
+						extrinsics.extend(
+							applied
+								.iter()
+								.filter(|(_, result)| result.is_ok())
+								.map(|(xt, _)| xt.clone()),
+						);
+					}
+					TransactionOutcome::Commit(Ok(applied))
+				},
+				Err(e) => TransactionOutcome::Rollback(Err(Error::from(e))),
+			}
+		});
+
+		self.timings.inherents += started.elapsed();
+		applied
+	}
+
+	/// The time spent in the individual phases of building the block so far.
+	pub fn timings(&self) -> BlockBuildingTimings {
+		self.timings
 	}
 
 	/// Estimate the size of the block in the current state.
@@ -375,12 +593,81 @@ where
 	}
 }
 
+/// Access to the block being built from within [`BlockBuilder::with_checkpoint`].
+pub struct Checkpoint<'b, Block: BlockT, Api> {
+	api: &'b Api,
+	parent_hash: Block::Hash,
+	version: u32,
+	extrinsics: &'b mut Vec<Block::Extrinsic>,
+	timings: &'b mut BlockBuildingTimings,
+}
+
+impl<'b, Block, Api> Checkpoint<'b, Block, Api>
+where
+	Block: BlockT,
+	Api: ApiExt<Block> + BlockBuilderApi<Block>,
+{
+	/// Push onto the block's list of extrinsics.
+	///
+	/// Same as [`BlockBuilder::push`], but the extrinsic is removed again if the checkpoint is
+	/// rolled back.
+	pub fn push(&mut self, xt: <Block as BlockT>::Extrinsic) -> Result<(), Error> {
+		push_extrinsic(self.api, self.parent_hash, self.version, self.extrinsics, self.timings, xt)
+	}
+
+	/// The extrinsics of the block, including the ones pushed since the checkpoint.
+	pub fn extrinsics(&self) -> &[Block::Extrinsic] {
+		self.extrinsics
+	}
+}
+
+/// Apply `xt` and add it to `extrinsics` if it was applied successfully.
+fn push_extrinsic<Block, Api>(
+	api: &Api,
+	parent_hash: Block::Hash,
+	version: u32,
+	extrinsics: &mut Vec<Block::Extrinsic>,
+	timings: &mut BlockBuildingTimings,
+	xt: <Block as BlockT>::Extrinsic,
+) -> Result<(), Error>
+where
+	Block: BlockT,
+	Api: ApiExt<Block> + BlockBuilderApi<Block>,
+{
+	let started = Instant::now();
+
+	let res = api.execute_in_transaction(|api| {
+		let res = if version < 6 {
+			#[allow(deprecated)]
+			api.apply_extrinsic_before_version_6(parent_hash, xt.clone())
+				.map(legacy::byte_sized_error::convert_to_latest)
+		} else {
+			api.apply_extrinsic(parent_hash, xt.clone())
+		};
+
+		match res {
+			Ok(Ok(_)) => {
+				extrinsics.push(xt);
+				TransactionOutcome::Commit(Ok(()))
+			},
+			Ok(Err(tx_validity)) => TransactionOutcome::Rollback(Err(
+				ApplyExtrinsicFailed::Validity(tx_validity).into(),
+			)),
+			Err(e) => TransactionOutcome::Rollback(Err(Error::from(e))),
+		}
+	});
+
+	timings.extrinsics += started.elapsed();
+	res
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use sp_blockchain::HeaderBackend;
 	use sp_core::Blake2Hasher;
 	use sp_state_machine::Backend;
+	use std::time::Duration;
 	use substrate_test_runtime_client::{
 		runtime::ExtrinsicBuilder, DefaultTestClientBuilderExt, TestClientBuilderExt,
 	};
@@ -462,4 +749,258 @@ mod tests {
 		assert!(proof_without_panic > proof_empty_block);
 		assert_eq!(proof_empty_block, proof_with_panic);
 	}
+
+	#[test]
+	fn block_building_phases_are_timed() {
+		let client = substrate_test_runtime_client::TestClientBuilder::new().build();
+		let genesis_hash = client.info().best_hash;
+
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+
+		assert_eq!(block_builder.timings(), BlockBuildingTimings::default());
+
+		for inherent in block_builder.create_inherents(Default::default()).unwrap() {
+			block_builder.push(inherent).unwrap();
+		}
+		block_builder.push(ExtrinsicBuilder::new_read(8).build()).unwrap();
+
+		let timings = block_builder.build().unwrap().timings;
+		assert!(timings.inherents > Duration::ZERO);
+		assert!(timings.extrinsics > Duration::ZERO);
+		assert!(timings.finalize > Duration::ZERO);
+		assert_eq!(timings.total(), timings.inherents + timings.extrinsics + timings.finalize);
+	}
+
+	#[test]
+	fn block_with_applied_inherents_can_be_imported() {
+		use substrate_test_runtime_client::{prelude::block_on, BlockOrigin, ClientBlockImportExt};
+
+		let client = substrate_test_runtime_client::TestClientBuilder::new().build();
+		let genesis_hash = client.info().best_hash;
+
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+
+		// The test runtime does not create any inherents.
+		assert!(matches!(
+			block_builder.apply_inherents(Default::default()).unwrap(),
+			Ok(applied) if applied.is_empty()
+		));
+		assert!(block_builder.timings().inherents > Duration::ZERO);
+		block_builder.push(ExtrinsicBuilder::new_read(8).build()).unwrap();
+
+		let block = block_builder.build().unwrap().block;
+		block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		assert_eq!(client.info().best_hash, block.hash());
+	}
+
+	#[test]
+	fn undecodable_extrinsic_is_skipped() {
+		use sp_runtime::transaction_validity::TransactionValidityError;
+
+		let client = substrate_test_runtime_client::TestClientBuilder::new().build();
+		let genesis_hash = client.info().best_hash;
+		let encoded = ExtrinsicBuilder::new_read(8).build().encode();
+
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+
+		assert!(matches!(
+			block_builder.push_encoded(encoded[..encoded.len() - 1].to_vec()),
+			Err(Error::ApplyExtrinsicFailed(ApplyExtrinsicFailed::Validity(
+				TransactionValidityError::Invalid(InvalidTransaction::Call)
+			)))
+		));
+		block_builder.push_encoded(encoded).unwrap();
+
+		assert_eq!(block_builder.build().unwrap().block.extrinsics().len(), 1);
+	}
+
+	#[test]
+	fn rolled_back_checkpoint_discards_extrinsics_and_state() {
+		let client = substrate_test_runtime_client::TestClientBuilder::new().build();
+		let genesis_hash = client.info().best_hash;
+		let key = b"checkpoint".to_vec();
+
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+
+		let pushed = block_builder.with_checkpoint(|checkpoint| {
+			checkpoint
+				.push(ExtrinsicBuilder::new_storage_change(key.clone(), Some(vec![1])).build())
+				.unwrap();
+			TransactionOutcome::Rollback(checkpoint.extrinsics().len())
+		});
+		assert_eq!(pushed, 1);
+
+		block_builder.with_checkpoint(|checkpoint| {
+			assert!(checkpoint.extrinsics().is_empty());
+			checkpoint.push(ExtrinsicBuilder::new_read(8).build()).unwrap();
+			TransactionOutcome::Commit(())
+		});
+
+		// Building the block checks the extrinsics root of the runtime against the pushed
+		// extrinsics, which only matches if the extrinsic index was rolled back as well.
+		let block = block_builder.build().unwrap();
+		assert_eq!(block.block.extrinsics().len(), 1);
+		assert!(!block.storage_changes.main_storage_changes.iter().any(|(k, _)| *k == key));
+	}
+
+	#[test]
+	fn optimize_inclusion_prefers_order_with_more_fees() {
+		use sp_runtime::Perbill;
+		use substrate_test_runtime_client::Sr25519Keyring;
+
+		let client = substrate_test_runtime_client::TestClientBuilder::new().build();
+		let genesis_hash = client.info().best_hash;
+
+		// Only 75% of the block are available for normal extrinsics, so either `big` or both
+		// `small` extrinsics fit into the block.
+		let candidate = |signer: Sr25519Keyring, percent: u32, fee: u128| InclusionCandidate {
+			extrinsic: ExtrinsicBuilder::new_fill_block(Perbill::from_percent(percent))
+				.signer(signer.pair())
+				.build(),
+			fee,
+			weight: Weight::from_parts(percent as u64, 0),
+		};
+		let big = candidate(Sr25519Keyring::Alice, 60, 10);
+		// Distinct fees, so the order of the `small` extrinsics does not depend on their hashes.
+		let small_1 = candidate(Sr25519Keyring::Bob, 35, 9);
+		let small_2 = candidate(Sr25519Keyring::Charlie, 35, 8);
+		let candidates = vec![big.clone(), small_1.clone(), small_2.clone()];
+
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+
+		// Swapping `big` and `small_1` would include both `small` extrinsics, so only the order
+		// given by the strategy is tried.
+		assert_eq!(
+			block_builder.optimize_inclusion(candidates.clone(), InclusionStrategy::GreedyByFee, 1),
+			vec![big.extrinsic],
+		);
+		assert_eq!(
+			block_builder.optimize_inclusion(
+				candidates,
+				InclusionStrategy::GreedyByFeePerWeight,
+				2
+			),
+			vec![small_1.extrinsic.clone(), small_2.extrinsic.clone()],
+		);
+
+		// Evaluating the candidates did not change the block.
+		block_builder.push(small_1.extrinsic).unwrap();
+		block_builder.push(small_2.extrinsic).unwrap();
+		assert_eq!(block_builder.build().unwrap().block.extrinsics().len(), 2);
+	}
+
+	#[test]
+	fn optimize_inclusion_tries_up_to_max_orderings() {
+		use sp_runtime::Perbill;
+		use substrate_test_runtime_client::Sr25519Keyring;
+
+		let client = substrate_test_runtime_client::TestClientBuilder::new().build();
+		let genesis_hash = client.info().best_hash;
+
+		// Only 75% of the block are available for normal extrinsics, so `first` fits together
+		// with neither of the others, but `second` and `third` fit together.
+		let candidate = |signer: Sr25519Keyring, percent: u32, fee: u128| InclusionCandidate {
+			extrinsic: ExtrinsicBuilder::new_fill_block(Perbill::from_percent(percent))
+				.signer(signer.pair())
+				.build(),
+			fee,
+			weight: Weight::from_parts(percent as u64, 0),
+		};
+		let first = candidate(Sr25519Keyring::Alice, 50, 10);
+		let second = candidate(Sr25519Keyring::Bob, 30, 9);
+		let third = candidate(Sr25519Keyring::Charlie, 30, 8);
+		// Neither the strategy order nor the given order include `second` and `third`, only
+		// swapping the first two candidates of the strategy order does.
+		let candidates = vec![first.clone(), third.clone(), second.clone()];
+
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+
+		assert!(block_builder
+			.optimize_inclusion(candidates.clone(), InclusionStrategy::GreedyByFee, 0)
+			.is_empty());
+		for max_orderings in [1, 2] {
+			assert_eq!(
+				block_builder.optimize_inclusion(
+					candidates.clone(),
+					InclusionStrategy::GreedyByFee,
+					max_orderings
+				),
+				vec![first.extrinsic.clone()],
+			);
+		}
+		assert_eq!(
+			block_builder.optimize_inclusion(candidates, InclusionStrategy::GreedyByFee, 3),
+			vec![second.extrinsic, third.extrinsic],
+		);
+	}
+
+	#[test]
+	fn optimize_inclusion_breaks_ties_by_extrinsic_hash() {
+		use sp_runtime::Perbill;
+		use substrate_test_runtime_client::{runtime::Block, Sr25519Keyring};
+
+		let client = substrate_test_runtime_client::TestClientBuilder::new().build();
+		let genesis_hash = client.info().best_hash;
+
+		let candidate = |signer: Sr25519Keyring| InclusionCandidate {
+			extrinsic: ExtrinsicBuilder::new_fill_block(Perbill::from_percent(10))
+				.signer(signer.pair())
+				.build(),
+			fee: 5,
+			weight: Weight::from_parts(10, 0),
+		};
+		let alice = candidate(Sr25519Keyring::Alice);
+		let bob = candidate(Sr25519Keyring::Bob);
+
+		let mut expected = vec![alice.extrinsic.clone(), bob.extrinsic.clone()];
+		expected.sort_by_key(|extrinsic| HashingFor::<Block>::hash_of(extrinsic));
+
+		for strategy in [InclusionStrategy::GreedyByFee, InclusionStrategy::GreedyByFeePerWeight] {
+			let mut first = BlockBuilderBuilder::new(&client)
+				.on_parent_block(genesis_hash)
+				.with_parent_block_number(0)
+				.build()
+				.unwrap();
+			let mut second = BlockBuilderBuilder::new(&client)
+				.on_parent_block(genesis_hash)
+				.with_parent_block_number(0)
+				.build()
+				.unwrap();
+
+			for _ in 0..3 {
+				assert_eq!(
+					first.optimize_inclusion(vec![alice.clone(), bob.clone()], strategy, 4),
+					expected,
+				);
+				assert_eq!(
+					second.optimize_inclusion(vec![bob.clone(), alice.clone()], strategy, 4),
+					expected,
+				);
+			}
+		}
+	}
 }
@@ -115,19 +115,22 @@ mod tests;
 
 extern crate alloc;
 
+use alloc::vec::Vec;
 use codec::{Codec, Encode};
 use core::marker::PhantomData;
 use frame_support::{
 	defensive_assert,
 	dispatch::{DispatchClass, DispatchInfo, GetDispatchInfo, PostDispatchInfo},
+	inherent::{CheckInherentsResult, InherentData},
 	migrations::MultiStepMigrator,
 	pallet_prelude::InvalidTransaction,
+	storage::{with_transaction_unchecked, TransactionOutcome},
 	traits::{
 		BeforeAllRuntimeMigrations, ExecuteBlock, Get, IsInherent, OffchainWorker, OnFinalize,
 		OnIdle, OnInitialize, OnPoll, OnRuntimeUpgrade, PostInherents, PostTransactions,
 		PreInherents,
 	},
-	weights::{Weight, WeightMeter},
+	weights::{Weight, WeightMeter, WeightReport},
 	MAX_EXTRINSIC_DEPTH,
 };
 use frame_system::pallet_prelude::BlockNumberFor;
@@ -815,6 +818,25 @@ where
 			Block::Extrinsic,
 			&Context,
 		) -> Result<CheckedOf<Block::Extrinsic, Context>, TransactionValidityError>,
+	) -> ApplyExtrinsicResult {
+		Self::do_apply_extrinsic_with_weight_report(
+			uxt,
+			is_inherent,
+			check,
+			&mut Default::default(),
+		)
+	}
+
+	/// Same as [`Self::do_apply_extrinsic`], but also fills `weight_report` once the extrinsic
+	/// was dispatched.
+	fn do_apply_extrinsic_with_weight_report(
+		uxt: Block::Extrinsic,
+		is_inherent: bool,
+		check: impl FnOnce(
+			Block::Extrinsic,
+			&Context,
+		) -> Result<CheckedOf<Block::Extrinsic, Context>, TransactionValidityError>,
+		weight_report: &mut WeightReport,
 	) -> ApplyExtrinsicResult {
 		sp_io::init_tracing();
 		let encoded = uxt.encode();
@@ -846,6 +868,14 @@ where
 
 		let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
+		let announced = dispatch_info.total_weight();
+		let post_info = match &r {
+			Ok(post_info) => post_info,
+			Err(err) => &err.post_info,
+		};
+		*weight_report =
+			WeightReport { announced, actual: post_info.actual_weight.unwrap_or(announced) };
+
 		// Mandatory(inherents) are not allowed to fail.
 		//
 		// The entire block should be discarded if an inherent fails to apply. Otherwise
@@ -868,6 +898,122 @@ where
 		Self::do_apply_extrinsic(uxt, is_inherent, Block::Extrinsic::check)
 	}
 
+	/// Apply extrinsic outside of the block execution function and report its weight.
+	///
+	/// Same as [`Self::apply_extrinsic`], but additionally returns the weight the extrinsic
+	/// announced before dispatch and the weight it reported after dispatch. This helps finding
+	/// extrinsics that underestimate their pre-dispatch weight, which is otherwise only visible
+	/// as an error log. The report is zero if the extrinsic was not dispatched.
+	pub fn apply_extrinsic_with_weight_report(
+		uxt: Block::Extrinsic,
+	) -> (ApplyExtrinsicResult, WeightReport) {
+		let is_inherent = System::is_inherent(&uxt);
+		let mut weight_report = WeightReport::default();
+		let result = Self::do_apply_extrinsic_with_weight_report(
+			uxt,
+			is_inherent,
+			Block::Extrinsic::check,
+			&mut weight_report,
+		);
+
+		(result, weight_report)
+	}
+
+	/// Apply an encoded extrinsic outside of the block execution function.
+	///
+	/// Same as [`Self::apply_extrinsic`], but an extrinsic that fails to decode is reported as
+	/// [`InvalidTransaction::Call`] instead of aborting the call. This allows the block author to
+	/// skip the malformed extrinsic and continue with the next one.
+	pub fn try_apply_extrinsic(encoded: &[u8]) -> ApplyExtrinsicResult {
+		let uxt = <Block::Extrinsic as codec::DecodeLimit>::decode_all_with_depth_limit(
+			MAX_EXTRINSIC_DEPTH,
+			&mut &encoded[..],
+		)
+		.map_err(|_| InvalidTransaction::Call)?;
+
+		Self::apply_extrinsic(uxt)
+	}
+
+	/// Apply each of the given extrinsics on top of the current state, independently of each
+	/// other.
+	///
+	/// Unlike applying them one after another, the changes of every extrinsic are rolled back
+	/// before the next one is applied, so each result reflects only the state this was called
+	/// with. Every extrinsic is applied in its own storage transaction, which costs one overlay
+	/// layer per extrinsic.
+	pub fn dry_run_extrinsics(extrinsics: Vec<Block::Extrinsic>) -> Vec<ApplyExtrinsicResult> {
+		extrinsics
+			.into_iter()
+			.map(|uxt| {
+				with_transaction_unchecked(|| {
+					TransactionOutcome::Rollback(Self::apply_extrinsic(uxt))
+				})
+			})
+			.collect()
+	}
+
+	/// Create the inherents for `data` and check them against the same `data`.
+	///
+	/// `create_extrinsics` and `check_extrinsics` are expected to be the runtime's
+	/// `InherentDataExt` functions generated by `construct_runtime!`. The exact extrinsics that
+	/// were checked are returned together with the result of the check, so a block author can
+	/// fail fast if its own inherents would not pass.
+	pub fn generate_and_check_inherents(
+		data: InherentData,
+		create_extrinsics: impl FnOnce(&InherentData) -> Vec<Block::Extrinsic>,
+		check_extrinsics: impl FnOnce(&InherentData, &Block::LazyBlock) -> CheckInherentsResult,
+	) -> (Vec<Block::Extrinsic>, CheckInherentsResult) {
+		let inherents = create_extrinsics(&data);
+
+		// Only the extrinsics are inspected by the check, the header is a placeholder.
+		let header = <Block::Header as Header>::new(
+			<frame_system::Pallet<System>>::block_number(),
+			Default::default(),
+			Default::default(),
+			<frame_system::Pallet<System>>::parent_hash(),
+			Default::default(),
+		);
+		let block = Block::new(header, inherents);
+		let checked = check_extrinsics(&data, &block.clone().into());
+
+		(block.deconstruct().1, checked)
+	}
+
+	/// Create the inherents for `data` and apply them outside of the block execution function.
+	///
+	/// The created inherents are checked first, see [`Self::generate_and_check_inherents`], so a
+	/// missing required inherent is reported as an error without touching the block being built.
+	/// Otherwise each inherent is returned together with the result of applying it, in the order
+	/// they were created. An inherent that fails to apply is rolled back, so only the inherents
+	/// that applied successfully end up in the block.
+	pub fn apply_inherents(
+		data: InherentData,
+		create_extrinsics: impl FnOnce(&InherentData) -> Vec<Block::Extrinsic>,
+		check_extrinsics: impl FnOnce(&InherentData, &Block::LazyBlock) -> CheckInherentsResult,
+	) -> Result<Vec<(Block::Extrinsic, ApplyExtrinsicResult)>, CheckInherentsResult> {
+		let (inherents, checked) =
+			Self::generate_and_check_inherents(data, create_extrinsics, check_extrinsics);
+		if !checked.ok() {
+			return Err(checked)
+		}
+
+		Ok(inherents
+			.into_iter()
+			.map(|uxt| {
+				let result = with_transaction_unchecked(|| {
+					let result = Self::apply_extrinsic(uxt.clone());
+					if result.is_ok() {
+						TransactionOutcome::Commit(result)
+					} else {
+						TransactionOutcome::Rollback(result)
+					}
+				});
+
+				(uxt, result)
+			})
+			.collect())
+	}
+
 	fn final_checks(header: &frame_system::pallet_prelude::HeaderFor<System>) {
 		sp_tracing::enter_span!(sp_tracing::Level::TRACE, "final_checks");
 		// remove temporaries
@@ -285,6 +285,82 @@ mod custom2 {
 	}
 }
 
+#[frame_support::pallet(dev_mode)]
+mod custom3 {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::weight(10)]
+		pub fn underestimated_weight(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			frame_system::ensure_signed(origin)?;
+			Ok(Some(Weight::from_parts(1_000, 0)).into())
+		}
+
+		#[pallet::weight((0, DispatchClass::Mandatory))]
+		pub fn inherent(origin: OriginFor<T>) -> DispatchResult {
+			frame_system::ensure_none(origin)?;
+			Ok(())
+		}
+	}
+
+	// The inherent is required whenever inherent data is provided, but only created if that data
+	// is `true`.
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T> {
+		type Call = Call<T>;
+
+		type Error = sp_inherents::MakeFatalError<()>;
+
+		const INHERENT_IDENTIFIER: [u8; 8] = *b"test1236";
+
+		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+			data.get_data::<bool>(&Self::INHERENT_IDENTIFIER)
+				.ok()
+				.flatten()
+				.filter(|create| *create)
+				.map(|_| Call::inherent {})
+		}
+
+		fn is_inherent_required(data: &InherentData) -> Result<Option<Self::Error>, Self::Error> {
+			Ok(data
+				.get_data::<bool>(&Self::INHERENT_IDENTIFIER)
+				.ok()
+				.flatten()
+				.map(|_| ().into()))
+		}
+
+		fn is_inherent(call: &Self::Call) -> bool {
+			*call == Call::<T>::inherent {}
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			match call {
+				Call::inherent { .. } => Ok(()),
+				_ => Err(UnknownTransaction::NoUnsignedValidator.into()),
+			}
+		}
+
+		fn validate_unsigned(
+			_source: TransactionSource,
+			_call: &Self::Call,
+		) -> TransactionValidity {
+			UnknownTransaction::NoUnsignedValidator.into()
+		}
+	}
+}
+
 frame_support::construct_runtime!(
 	pub struct Runtime
 	{
@@ -293,6 +369,7 @@ frame_support::construct_runtime!(
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>},
 		Custom: custom::{Pallet, Call, ValidateUnsigned, Inherent},
 		Custom2: custom2::{Pallet, Call, ValidateUnsigned, Inherent},
+		Custom3: custom3::{Pallet, Call, ValidateUnsigned, Inherent},
 	}
 );
 
@@ -449,6 +526,7 @@ impl pallet_transaction_payment::Config for Runtime {
 
 impl custom::Config for Runtime {}
 impl custom2::Config for Runtime {}
+impl custom3::Config for Runtime {}
 
 pub struct RuntimeVersion;
 impl frame_support::traits::Get<sp_version::RuntimeVersion> for RuntimeVersion {
@@ -741,6 +819,183 @@ fn bad_extrinsic_not_inserted() {
 	});
 }
 
+#[test]
+fn undecodable_extrinsic_is_rejected_and_builder_remains_usable() {
+	let mut t = new_test_ext(10);
+	let xt = UncheckedXt::new_signed(call_transfer(2, 69), 1, 1.into(), tx_ext(0, 0));
+	let encoded = xt.encode();
+	t.execute_with(|| {
+		Executive::initialize_block(&Header::new_from_number(1));
+		assert_err!(
+			Executive::try_apply_extrinsic(&encoded[..encoded.len() - 1]),
+			TransactionValidityError::Invalid(InvalidTransaction::Call)
+		);
+		assert_eq!(<frame_system::Pallet<Runtime>>::extrinsic_index(), Some(0));
+
+		assert_ok!(Executive::try_apply_extrinsic(&encoded).unwrap());
+		assert_eq!(<frame_system::Pallet<Runtime>>::extrinsic_index(), Some(1));
+	});
+}
+
+#[test]
+fn dry_run_extrinsics_applies_each_extrinsic_independently() {
+	// Both transfers use the same nonce, so only one of them could be included in a block.
+	let xt = UncheckedXt::new_signed(call_transfer(2, 69), 1, 1.into(), tx_ext(0, 0));
+
+	new_test_ext(1).execute_with(|| {
+		Executive::initialize_block(&Header::new_from_number(1));
+		assert_eq!(
+			Executive::dry_run_extrinsics(vec![xt.clone(), xt.clone()]),
+			vec![Ok(Ok(())), Ok(Ok(()))]
+		);
+
+		// Nothing was applied.
+		assert_eq!(<pallet_balances::Pallet<Runtime>>::total_balance(&2), 0);
+		assert_eq!(<frame_system::Pallet<Runtime>>::extrinsic_index(), Some(0));
+		assert!(Executive::apply_extrinsic(xt.clone()).unwrap().is_ok());
+		assert_err!(
+			Executive::apply_extrinsic(xt),
+			TransactionValidityError::Invalid(InvalidTransaction::Stale)
+		);
+	});
+}
+
+#[test]
+fn apply_extrinsic_with_weight_report_reports_overshoot() {
+	let call = RuntimeCall::Custom3(custom3::Call::underestimated_weight {});
+	let xt = UncheckedXt::new_signed(call, 1, 1.into(), tx_ext(0, 0));
+	let announced = xt.get_dispatch_info().total_weight();
+
+	new_test_ext(1).execute_with(|| {
+		Executive::initialize_block(&Header::new_from_number(1));
+		let (result, report) = Executive::apply_extrinsic_with_weight_report(xt);
+
+		assert_eq!(result, Ok(Ok(())));
+		assert_eq!(report.announced, announced);
+		assert!(report.actual.any_gt(report.announced));
+		assert_eq!(report.overshoot(), report.actual - report.announced);
+	});
+}
+
+#[test]
+fn apply_inherents_applies_created_inherents() {
+	let mut data = InherentData::new();
+	data.put_data(custom3::Pallet::<Runtime>::INHERENT_IDENTIFIER, &true).unwrap();
+
+	new_test_ext(1).execute_with(|| {
+		Executive::initialize_block(&Header::new_from_number(1));
+		let results = Executive::apply_inherents(
+			data,
+			|data| data.create_extrinsics(),
+			|data, block| data.check_extrinsics(block),
+		)
+		.unwrap();
+
+		assert_eq!(
+			results,
+			vec![(
+				UncheckedXt::new_bare(RuntimeCall::Custom3(custom3::Call::inherent {})),
+				Ok(Ok(()))
+			)]
+		);
+		assert_eq!(<frame_system::Pallet<Runtime>>::extrinsic_index(), Some(1));
+	});
+}
+
+#[test]
+fn apply_inherents_rejects_missing_required_inherent() {
+	let mut data = InherentData::new();
+	data.put_data(custom3::Pallet::<Runtime>::INHERENT_IDENTIFIER, &false).unwrap();
+
+	new_test_ext(1).execute_with(|| {
+		Executive::initialize_block(&Header::new_from_number(1));
+		let checked = Executive::apply_inherents(
+			data,
+			|data| data.create_extrinsics(),
+			|data, block| data.check_extrinsics(block),
+		)
+		.unwrap_err();
+
+		assert!(checked.fatal_error());
+		assert!(checked
+			.get_error::<()>(&custom3::Pallet::<Runtime>::INHERENT_IDENTIFIER)
+			.unwrap()
+			.is_some());
+		assert_eq!(<frame_system::Pallet<Runtime>>::extrinsic_index(), Some(0));
+	});
+}
+
+#[test]
+fn generate_and_check_inherents_reports_failure() {
+	let mut data = InherentData::new();
+	data.put_data(custom3::Pallet::<Runtime>::INHERENT_IDENTIFIER, &true).unwrap();
+
+	new_test_ext(1).execute_with(|| {
+		let (inherents, checked) = Executive::generate_and_check_inherents(
+			data.clone(),
+			|data| data.create_extrinsics(),
+			|data, block| data.check_extrinsics(block),
+		);
+		assert_eq!(
+			inherents,
+			vec![UncheckedXt::new_bare(RuntimeCall::Custom3(custom3::Call::inherent {}))]
+		);
+		assert!(checked.ok());
+
+		// Requiring an inherent that is never created makes the check fail.
+		data.replace_data(custom3::Pallet::<Runtime>::INHERENT_IDENTIFIER, &false);
+		let (inherents, checked) = Executive::generate_and_check_inherents(
+			data,
+			|data| data.create_extrinsics(),
+			|data, block| data.check_extrinsics(block),
+		);
+		assert!(inherents.is_empty());
+		assert!(checked.fatal_error());
+	});
+}
+
+#[test]
+fn check_extrinsics_with_ordering_reports_misordered_inherents() {
+	use frame_support::inherent::{InherentOrderingError, INHERENT_ORDERING_IDENTIFIER};
+
+	let custom = custom::Pallet::<Runtime>::INHERENT_IDENTIFIER;
+	let custom2 = custom2::Pallet::<Runtime>::INHERENT_IDENTIFIER;
+	let ordering = [(custom, custom2)];
+	let custom_xt = || UncheckedXt::new_bare(RuntimeCall::Custom(custom::Call::inherent {}));
+	let custom2_xt = || UncheckedXt::new_bare(RuntimeCall::Custom2(custom2::Call::inherent {}));
+	let block = |xts| -> <TestBlock as BlockT>::LazyBlock {
+		TestBlock::new(Header::new_from_number(1), xts).into()
+	};
+
+	new_test_ext(1).execute_with(|| {
+		let checked = InherentData::new()
+			.check_extrinsics_with_ordering(&block(vec![custom_xt(), custom2_xt()]), &ordering);
+		assert!(checked.ok());
+
+		let checked = InherentData::new()
+			.check_extrinsics_with_ordering(&block(vec![custom2_xt(), custom_xt()]), &ordering);
+		assert!(checked.fatal_error());
+		assert_eq!(
+			checked
+				.get_error::<InherentOrderingError>(&INHERENT_ORDERING_IDENTIFIER)
+				.unwrap(),
+			Some(InherentOrderingError { before: custom, after: custom2 }),
+		);
+
+		// A missing inherent is reported under its own identifier, not as an ordering violation.
+		let custom3 = custom3::Pallet::<Runtime>::INHERENT_IDENTIFIER;
+		let mut data = InherentData::new();
+		data.put_data(custom3, &false).unwrap();
+		let checked = data.check_extrinsics_with_ordering(&block(vec![custom_xt()]), &ordering);
+		assert!(checked.fatal_error());
+		assert!(checked.get_error::<()>(&custom3).unwrap().is_some());
+		assert!(checked
+			.get_error::<InherentOrderingError>(&INHERENT_ORDERING_IDENTIFIER)
+			.unwrap()
+			.is_none());
+	});
+}
+
 #[test]
 fn block_weight_limit_enforced() {
 	let mut t = new_test_ext(10000);
@@ -257,11 +257,23 @@ pub fn create2(deployer: &H160, code: &[u8], input_data: &[u8], salt: &[u8; 32])
 		let init_code: Vec<u8> = code.into_iter().chain(input_data).cloned().collect();
 		keccak_256(init_code.as_ref())
 	};
+	create2_from_init_code_hash(deployer, salt, &init_code_hash)
+}
+
+/// Determine the address of a contract using the CREATE2 semantics, given the keccak hash of
+/// its init code.
+///
+/// Useful to verify CREATE2 factories without having the full init code at hand.
+pub fn create2_from_init_code_hash(
+	deployer: &H160,
+	salt: &[u8; 32],
+	init_code_hash: &[u8; 32],
+) -> H160 {
 	let mut bytes = [0; 85];
 	bytes[0] = 0xff;
 	bytes[1..21].copy_from_slice(deployer.as_bytes());
 	bytes[21..53].copy_from_slice(salt);
-	bytes[53..85].copy_from_slice(&init_code_hash);
+	bytes[53..85].copy_from_slice(init_code_hash);
 	let hash = keccak_256(&bytes);
 	H160::from_slice(&hash[12..])
 }
@@ -272,7 +284,7 @@ mod test {
 	use crate::{
 		test_utils::*,
 		tests::{ExtBuilder, Test},
-		AddressMapper, Error,
+		AddressMapper, ContractBlob, Error,
 	};
 	use frame_support::{
 		assert_err,
@@ -302,6 +314,69 @@ mod test {
 		)
 	}
 
+	#[test]
+	fn create1_matches_ethereum_vectors() {
+		let deployer = H160(hex2array!("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0"));
+		let expected = [
+			hex2array!("cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"),
+			hex2array!("343c43a37d37dff08ae8c4a11544c718abb4fcf8"),
+			hex2array!("f778b86fa74e846c4f0a1fbd1335fe81c00a0c91"),
+			hex2array!("fffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c"),
+		];
+		for (nonce, expected) in expected.into_iter().enumerate() {
+			assert_eq!(create1(&deployer, nonce as u64), H160(expected));
+			assert_eq!(
+				ContractBlob::<Test>::predict_create_address(&deployer, nonce as u64),
+				H160(expected)
+			);
+		}
+	}
+
+	#[test]
+	fn create2_matches_eip1014_vectors() {
+		let vectors: [(H160, [u8; 32], &[u8], H160); 4] = [
+			(
+				H160::zero(),
+				[0; 32],
+				&hex2array!("00"),
+				H160(hex2array!("4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38")),
+			),
+			(
+				H160(hex2array!("deadbeef00000000000000000000000000000000")),
+				[0; 32],
+				&hex2array!("00"),
+				H160(hex2array!("b928f69bb1d91cd65274e3c79d8986362984fda3")),
+			),
+			(
+				H160(hex2array!("00000000000000000000000000000000deadbeef")),
+				hex2array!("00000000000000000000000000000000000000000000000000000000cafebabe"),
+				&hex2array!("deadbeef"),
+				H160(hex2array!("60f3f640a8508fc6a86d45df051962668e1e8ac7")),
+			),
+			(
+				H160::zero(),
+				[0; 32],
+				&[],
+				H160(hex2array!("e33c0c7f7df4809055c3eba6c09cfe4baf1bd9e0")),
+			),
+		];
+		for (deployer, salt, init_code, expected) in vectors {
+			assert_eq!(create2(&deployer, init_code, &[], &salt), expected);
+			assert_eq!(
+				create2_from_init_code_hash(&deployer, &salt, &keccak_256(init_code)),
+				expected
+			);
+			assert_eq!(
+				ContractBlob::<Test>::predict_create2_address(
+					&deployer,
+					&salt,
+					&keccak_256(init_code)
+				),
+				expected
+			);
+		}
+	}
+
 	#[test]
 	fn fallback_map_works() {
 		assert!(<Test as Config>::AddressMapper::is_mapped(&ALICE));
@@ -92,11 +92,18 @@ pub struct PrestateTracerConfig {
 
 	/// Whether to include code in the trace.
 	pub disable_code: bool,
+
+	/// Only include the storage of these contracts in the trace.
+	///
+	/// Maps a contract address to the storage keys to include, all keys are included if the
+	/// list is empty. The storage of all contracts is included if this is `None`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage_filter: Option<BTreeMap<H160, Vec<Bytes>>>,
 }
 
 impl Default for PrestateTracerConfig {
 	fn default() -> Self {
-		Self { diff_mode: false, disable_storage: false, disable_code: false }
+		Self { diff_mode: false, disable_storage: false, disable_code: false, storage_filter: None }
 	}
 }
 
@@ -163,6 +170,8 @@ pub enum CallType {
 	Create,
 	/// A create2 call.
 	Create2,
+	/// A contract registered its termination and transfers its balance to `to`.
+	Selfdestruct,
 }
 
 /// A Trace
@@ -122,6 +122,25 @@ impl<Gas: Default, GasMapper: Fn(Weight) -> Gas> Tracing for CallTracer<Gas, Gas
 		}
 	}
 
+	fn terminate(&mut self, contract: H160, beneficiary: H160, value: U256) {
+		if self.config.only_top_call {
+			return;
+		}
+
+		let current_index = self.current_stack.last().unwrap();
+
+		if let Some(trace) = self.traces.get_mut(*current_index) {
+			trace.child_call_count += 1;
+			trace.calls.push(CallTrace {
+				from: contract,
+				to: beneficiary,
+				value: Some(value),
+				call_type: CallType::Selfdestruct,
+				..Default::default()
+			});
+		}
+	}
+
 	fn log_event(&mut self, address: H160, topics: &[H256], data: &[u8]) {
 		if !self.config.with_logs {
 			return;
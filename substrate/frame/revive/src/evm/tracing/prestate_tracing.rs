@@ -137,6 +137,14 @@ where
 		return Some(code.into())
 	}
 
+	/// Whether the storage `key` of the current contract is included in the trace.
+	fn is_storage_traced(&self, key: &Bytes) -> bool {
+		let Some(filter) = &self.config.storage_filter else { return true };
+		filter
+			.get(&self.current_addr)
+			.is_some_and(|keys| keys.is_empty() || keys.contains(key))
+	}
+
 	/// Update the prestate info for the given address.
 	fn update_prestate_info(entry: &mut PrestateTraceInfo, addr: &H160, code: Option<Bytes>) {
 		let info = Self::prestate_info(addr, Pallet::<T>::evm_balance(addr), code);
@@ -240,6 +248,9 @@ where
 
 	fn storage_write(&mut self, key: &Key, old_value: Option<Vec<u8>>, new_value: Option<&[u8]>) {
 		let key = Bytes::from(key.unhashed().to_vec());
+		if !self.is_storage_traced(&key) {
+			return
+		}
 
 		let old_value = self
 			.trace
@@ -267,12 +278,17 @@ where
 	}
 
 	fn storage_read(&mut self, key: &Key, value: Option<&[u8]>) {
+		let key = Bytes::from(key.unhashed().to_vec());
+		if !self.is_storage_traced(&key) {
+			return
+		}
+
 		self.trace
 			.0
 			.entry(self.current_addr)
 			.or_default()
 			.storage
-			.entry(key.unhashed().to_vec().into())
+			.entry(key)
 			.or_insert_with(|| value.map(|v| v.to_vec().into()));
 	}
 
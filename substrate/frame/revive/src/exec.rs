@@ -29,6 +29,7 @@ use crate::{
 	storage::{self, meter::Diff, AccountIdOrAddress, WriteOutcome},
 	tracing::if_tracing,
 	transient_storage::TransientStorage,
+	vm::evm::BytecodeCache,
 	AccountInfo, AccountInfoOf, BalanceOf, BalanceWithDust, Code, CodeInfo, CodeInfoOf,
 	CodeRemoved, Config, ContractInfo, Error, Event, ImmutableData, ImmutableDataOf,
 	Pallet as Contracts, RuntimeCosts, LOG_TARGET,
@@ -54,6 +55,7 @@ use frame_system::{
 	pallet_prelude::{BlockNumberFor, OriginFor},
 	Pallet as System, RawOrigin,
 };
+use revm::bytecode::Bytecode;
 use sp_core::{
 	ecdsa::Public as ECDSAPublic,
 	sr25519::{Public as SR25519Public, Signature as SR25519Signature},
@@ -238,6 +240,12 @@ pub trait Ext: PrecompileWithInfoExt {
 	///
 	/// Note: Requires &mut self to access the contract info.
 	fn set_immutable_data(&mut self, data: ImmutableData) -> Result<(), DispatchError>;
+
+	/// Returns the analyzed EVM bytecode of the contract with `code_hash`.
+	///
+	/// Analyzing the bytecode derives its valid jump destinations. The result is cached for the
+	/// lifetime of the call stack, so `code` is only called if the contract was not analyzed yet.
+	fn evm_bytecode(&mut self, code_hash: H256, code: impl FnOnce() -> Vec<u8>) -> Bytecode;
 }
 
 /// Environment functions which are available to pre-compiles with `HAS_CONTRACT_INFO = true`.
@@ -568,6 +576,8 @@ pub struct Stack<'a, T: Config, E> {
 	/// The set of contracts that are registered for destruction at the end of this call stack.
 	/// The tuple contains: (address of contract, contract info, address of beneficiary)
 	contracts_to_be_destroyed: BTreeMap<H160, (ContractInfo<T>, H160)>,
+	/// The analyzed EVM bytecode of the contracts executed in this call stack.
+	evm_bytecode_cache: BytecodeCache,
 	/// No executable is held by the struct but influences its behaviour.
 	_phantom: PhantomData<E>,
 }
@@ -949,6 +959,7 @@ where
 			exec_config,
 			contracts_created: BTreeSet::new(),
 			contracts_to_be_destroyed: BTreeMap::new(),
+			evm_bytecode_cache: Default::default(),
 			_phantom: Default::default(),
 		};
 
@@ -1764,6 +1775,13 @@ where
 		};
 		self.contracts_to_be_destroyed
 			.insert(contract_address, (contract_info.clone(), *beneficiary));
+		if_tracing(|tracer| {
+			tracer.terminate(
+				contract_address,
+				*beneficiary,
+				<Contracts<T>>::evm_balance(&contract_address),
+			)
+		});
 
 		if self.contracts_created.contains(&account_id) {
 			Ok(CodeRemoved::Yes)
@@ -1841,6 +1859,10 @@ where
 		<ImmutableDataOf<T>>::insert(T::AddressMapper::to_address(&frame.account_id), &data);
 		Ok(())
 	}
+
+	fn evm_bytecode(&mut self, code_hash: H256, code: impl FnOnce() -> Vec<u8>) -> Bytecode {
+		self.evm_bytecode_cache.get_or_analyze(code_hash, code)
+	}
 }
 
 impl<'a, T, E> PrecompileWithInfoExt for Stack<'a, T, E>
@@ -2234,6 +2256,13 @@ where
 			AccountInfo::<T>::load_contract(&caller_address).ok_or(Error::<T>::ContractNotFound)?;
 		self.contracts_to_be_destroyed
 			.insert(caller_address, (contract_info.clone(), *beneficiary));
+		if_tracing(|tracer| {
+			tracer.terminate(
+				caller_address,
+				*beneficiary,
+				<Contracts<T>>::evm_balance(&caller_address),
+			)
+		});
 
 		// Pretend the contract was created in the current tx so that its storage can be destroyed.
 		self.contracts_created.insert(account_id);
@@ -24,21 +24,29 @@ use crate::{
 	transient_storage::TransientStorage,
 	Code, CodeRemoved, Config, ExecReturnValue, ImmutableData,
 };
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, vec::Vec};
 use core::marker::PhantomData;
 use frame_support::weights::Weight;
+use revm::bytecode::Bytecode;
 use sp_core::{H160, H256, U256};
 use sp_runtime::DispatchError;
 
-/// Mock implementation of the Ext trait that panics for all methods
+/// Mock implementation of the Ext trait that panics for most methods
+///
+/// Only the gas meter and the contract storage are implemented.
 pub struct MockExt<T: Config> {
 	gas_meter: GasMeter<T>,
+	storage: BTreeMap<Vec<u8>, Vec<u8>>,
 	_phantom: PhantomData<T>,
 }
 
 impl<T: Config> MockExt<T> {
 	pub fn new() -> Self {
-		Self { gas_meter: GasMeter::new(Weight::MAX), _phantom: PhantomData }
+		Self {
+			gas_meter: GasMeter::new(Weight::MAX),
+			storage: Default::default(),
+			_phantom: PhantomData,
+		}
 	}
 }
 
@@ -186,7 +194,7 @@ impl<T: Config> PrecompileExt for MockExt<T> {
 	}
 
 	fn is_read_only(&self) -> bool {
-		panic!("MockExt::is_read_only")
+		false
 	}
 
 	fn is_delegate_call(&self) -> bool {
@@ -221,21 +229,29 @@ impl<T: Config> PrecompileExt for MockExt<T> {
 		panic!("MockExt::gas_left")
 	}
 
-	fn get_storage(&mut self, _key: &Key) -> Option<Vec<u8>> {
-		panic!("MockExt::get_storage")
+	fn get_storage(&mut self, key: &Key) -> Option<Vec<u8>> {
+		self.storage.get(key.unhashed()).cloned()
 	}
 
-	fn get_storage_size(&mut self, _key: &Key) -> Option<u32> {
-		panic!("MockExt::get_storage_size")
+	fn get_storage_size(&mut self, key: &Key) -> Option<u32> {
+		self.storage.get(key.unhashed()).map(|value| value.len() as u32)
 	}
 
 	fn set_storage(
 		&mut self,
-		_key: &Key,
-		_value: Option<Vec<u8>>,
-		_take_old: bool,
+		key: &Key,
+		value: Option<Vec<u8>>,
+		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError> {
-		panic!("MockExt::set_storage")
+		let old = match value {
+			Some(value) => self.storage.insert(key.unhashed().to_vec(), value),
+			None => self.storage.remove(key.unhashed()),
+		};
+		Ok(match (old, take_old) {
+			(None, _) => WriteOutcome::New,
+			(Some(old), false) => WriteOutcome::Overwritten(old.len() as u32),
+			(Some(old), true) => WriteOutcome::Taken(old),
+		})
 	}
 
 	fn charge_storage(&mut self, _diff: &Diff) {}
@@ -289,4 +305,8 @@ impl<T: Config> Ext for MockExt<T> {
 	fn set_immutable_data(&mut self, _data: ImmutableData) -> Result<(), DispatchError> {
 		panic!("MockExt::set_immutable_data")
 	}
+
+	fn evm_bytecode(&mut self, _code_hash: H256, code: impl FnOnce() -> Vec<u8>) -> Bytecode {
+		Bytecode::new_raw(code().into())
+	}
 }
@@ -29,7 +29,7 @@ mod debug;
 mod exec;
 mod gas;
 mod impl_fungibles;
-mod limits;
+pub mod limits;
 mod primitives;
 mod storage;
 #[cfg(test)]
@@ -73,7 +73,7 @@ use frame_support::{
 	traits::{
 		fungible::{Balanced, Inspect, Mutate, MutateHold},
 		tokens::Balance,
-		ConstU32, ConstU64, EnsureOrigin, Get, IsSubType, IsType, OriginTrait, Time,
+		ConstU32, ConstU64, EnsureOrigin, Get, IsSubType, IsType, OriginTrait, Randomness, Time,
 	},
 	weights::WeightMeter,
 	BoundedVec, RuntimeDebugNoBound,
@@ -91,13 +91,18 @@ use sp_runtime::{
 
 pub use crate::{
 	address::{
-		create1, create2, is_eth_derived, AccountId32Mapper, AddressMapper, TestAccountMapper,
+		create1, create2, create2_from_init_code_hash, is_eth_derived, AccountId32Mapper,
+		AddressMapper, TestAccountMapper,
 	},
 	debug::DebugSettings,
 	evm::{block_hash::ReceiptGasInfo, Address as EthAddress, Block as EthBlock, ReceiptInfo},
 	exec::{Key, MomentOf, Origin as ExecOrigin},
 	pallet::{genesis, *},
 	storage::{AccountInfo, ContractInfo},
+	vm::evm::{
+		DifficultyMode, GasProfile, Halt, HaltReason, Memory, MemorySnapshot, NoRandomness,
+		OpcodePolicy, Stack,
+	},
 };
 pub use codec;
 pub use frame_support::{self, dispatch::DispatchInfo, weights::Weight};
@@ -332,6 +337,40 @@ pub mod pallet {
 		/// Allows debug-mode configuration, such as enabling unlimited contract size.
 		#[pallet::constant]
 		type DebugEnabled: Get<bool>;
+
+		/// The EVM opcodes that contracts are not allowed to execute.
+		///
+		/// Executing a denied opcode fails the call with [`Error::OpcodeDisabled`]. Use
+		/// [`frame_support::traits::GetDefault`] to allow all opcodes.
+		type EvmOpcodePolicy: Get<OpcodePolicy>;
+
+		/// The maximum amount of memory in bytes an EVM contract may use in a single call frame.
+		///
+		/// Growing the memory beyond this fails the call with [`Error::MemoryLimitExceeded`],
+		/// regardless of the gas left. Values above [`limits::EVM_MEMORY_BYTES`] have no effect,
+		/// as the memory accounting in [`Pallet::integrity_test`] is based on that limit.
+		#[pallet::constant]
+		type EvmMemoryLimit: Get<u32>;
+
+		/// The maximum size in bytes of the data an EVM contract may return or revert with.
+		///
+		/// Returning more fails the call with [`Error::ReturnDataTooLarge`] before the output is
+		/// copied out of the contract memory. Values above [`limits::CALLDATA_BYTES`] have no
+		/// effect, as that limit is enforced for all contracts.
+		#[pallet::constant]
+		type EvmReturnDataLimit: Get<u32>;
+
+		/// Selects the value returned by the EVM `DIFFICULTY`/`PREVRANDAO` opcode.
+		///
+		/// Use [`frame_support::traits::GetDefault`] to return the fixed pre-Merge constant.
+		type EvmDifficulty: Get<DifficultyMode>;
+
+		/// The randomness source read by the `DIFFICULTY` opcode in
+		/// [`DifficultyMode::Prevrandao`] mode.
+		///
+		/// Use [`NoRandomness`] if the runtime never enables that mode.
+		#[pallet::no_default_bounds]
+		type EvmRandomness: Randomness<H256, BlockNumberFor<Self>>;
 	}
 
 	/// Container for different types that implement [`DefaultConfig`]` of this pallet.
@@ -414,6 +453,11 @@ pub mod pallet {
 			type FeeInfo = ();
 			type MaxEthExtrinsicWeight = MaxEthExtrinsicWeight;
 			type DebugEnabled = ConstBool<false>;
+			type EvmOpcodePolicy = frame_support::traits::GetDefault;
+			type EvmMemoryLimit = ConstU32<{ limits::EVM_MEMORY_BYTES }>;
+			type EvmReturnDataLimit = ConstU32<{ limits::CALLDATA_BYTES }>;
+			type EvmDifficulty = frame_support::traits::GetDefault;
+			type EvmRandomness = NoRandomness;
 		}
 	}
 
@@ -542,7 +586,7 @@ pub mod pallet {
 		UnsupportedPrecompileAddress = 0x2F,
 		/// The calldata exceeds [`limits::CALLDATA_BYTES`].
 		CallDataTooLarge = 0x30,
-		/// The return data exceeds [`limits::CALLDATA_BYTES`].
+		/// The return data exceeds [`limits::CALLDATA_BYTES`] or [`Config::EvmReturnDataLimit`].
 		ReturnDataTooLarge = 0x31,
 		/// Invalid jump destination. Dynamic jumps points to invalid not jumpdest opcode.
 		InvalidJump = 0x32,
@@ -554,6 +598,10 @@ pub mod pallet {
 		///
 		/// This happens if the passed `gas` inside the ethereum transaction is too low.
 		TxFeeOverdraw = 0x35,
+		/// The contract executed an EVM opcode denied by [`Config::EvmOpcodePolicy`].
+		OpcodeDisabled = 0x36,
+		/// The EVM memory grew beyond [`Config::EvmMemoryLimit`].
+		MemoryLimitExceeded = 0x37,
 	}
 
 	/// A reason for the pallet revive placing a hold on funds.
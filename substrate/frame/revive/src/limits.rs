@@ -90,6 +90,9 @@ pub const IMMUTABLE_BYTES: u32 = 4 * 1024;
 /// upperbound of memory that can be used by the EVM interpreter.
 pub const EVM_MEMORY_BYTES: u32 = 1024 * 1024;
 
+/// The maximum number of EVM contracts whose analyzed bytecode is cached in a call stack.
+pub const EVM_BYTECODE_CACHE_ENTRIES: u32 = 16;
+
 /// EVM interpreter stack limit.
 pub const EVM_STACK_LIMIT: u32 = 1024;
 
@@ -273,7 +276,13 @@ const fn memory_required() -> u32 {
 	// The root frame is not accounted for in CALL_STACK_DEPTH
 	let max_call_depth = CALL_STACK_DEPTH + 1;
 
-	let per_stack_memory = code::PURGABLE_MEMORY_LIMIT + TRANSIENT_STORAGE_BYTES * 2;
+	// Analyzed EVM bytecode is padded by up to 33 bytes and has a jump table with one bit per byte.
+	let evm_max_analyzed_size = revm::primitives::eip170::MAX_CODE_SIZE as u32 + 33;
+	let evm_bytecode_cache =
+		EVM_BYTECODE_CACHE_ENTRIES * (evm_max_analyzed_size + evm_max_analyzed_size.div_ceil(8));
+
+	let per_stack_memory =
+		code::PURGABLE_MEMORY_LIMIT + TRANSIENT_STORAGE_BYTES * 2 + evm_bytecode_cache;
 
 	let evm_max_initcode_size = revm::primitives::eip3860::MAX_INITCODE_SIZE as u32;
 	let evm_overhead = EVM_MEMORY_BYTES + evm_max_initcode_size + EVM_STACK_LIMIT * 32;
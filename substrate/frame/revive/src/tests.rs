@@ -30,18 +30,19 @@ use crate::{
 	genesis::{Account, ContractData},
 	test_utils::*,
 	AccountId32Mapper, AddressMapper, BalanceOf, BalanceWithDust, Call, CodeInfoOf, Config,
-	ExecOrigin as Origin, GenesisConfig, OriginFor, Pallet, PristineCode,
+	DifficultyMode, ExecOrigin as Origin, GenesisConfig, OpcodePolicy, OriginFor, Pallet,
+	PristineCode,
 };
 use frame_support::{
 	assert_ok, derive_impl,
 	pallet_prelude::EnsureOrigin,
 	parameter_types,
-	traits::{ConstU32, ConstU64, FindAuthor, StorageVersion},
+	traits::{ConstU32, ConstU64, FindAuthor, Randomness, StorageVersion},
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, FixedFee, Weight},
 };
 use pallet_revive_fixtures::compile_module;
 use pallet_transaction_payment::{ChargeTransactionPayment, ConstFeeMultiplier, Multiplier};
-use sp_core::U256;
+use sp_core::{H256, U256};
 use sp_keystore::{testing::MemoryKeystore, KeystoreExt};
 use sp_runtime::{
 	generic::Header,
@@ -371,6 +372,11 @@ parameter_types! {
 	pub static AllowEvmBytecode: bool = true;
 	pub CheckingAccount: AccountId32 = BOB.clone();
 	pub static DebugFlag: bool = false;
+	pub static EvmOpcodes: OpcodePolicy = OpcodePolicy::allow_all();
+	pub static EvmMemoryLimit: u32 = crate::limits::EVM_MEMORY_BYTES;
+	pub static EvmReturnDataLimit: u32 = crate::limits::CALLDATA_BYTES;
+	pub static EvmDifficultyMode: DifficultyMode = DifficultyMode::default();
+	pub static EvmRandomSeed: H256 = H256::zero();
 }
 
 impl FindAuthor<<Test as frame_system::Config>::AccountId> for Test {
@@ -382,6 +388,12 @@ impl FindAuthor<<Test as frame_system::Config>::AccountId> for Test {
 	}
 }
 
+impl Randomness<H256, u64> for Test {
+	fn random(_subject: &[u8]) -> (H256, u64) {
+		(EvmRandomSeed::get(), System::block_number())
+	}
+}
+
 #[derive_impl(crate::config_preludes::TestDefaultConfig)]
 impl Config for Test {
 	type Time = Timestamp;
@@ -401,6 +413,11 @@ impl Config for Test {
 	type Precompiles = (precompiles::WithInfo<Self>, precompiles::NoInfo<Self>);
 	type FeeInfo = FeeInfo<Address, Signature, EthExtraImpl>;
 	type DebugEnabled = DebugFlag;
+	type EvmOpcodePolicy = EvmOpcodes;
+	type EvmMemoryLimit = EvmMemoryLimit;
+	type EvmReturnDataLimit = EvmReturnDataLimit;
+	type EvmDifficulty = EvmDifficultyMode;
+	type EvmRandomness = Test;
 }
 
 impl TryFrom<RuntimeCall> for Call<Test> {
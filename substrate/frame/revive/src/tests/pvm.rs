@@ -4369,6 +4369,7 @@ fn prestate_tracing_works() {
 					diff_mode: false,
 					disable_storage: false,
 					disable_code: false,
+					storage_filter: None,
 				},
 				PrestateTrace::Prestate(BTreeMap::from([
 					(
@@ -4413,6 +4414,7 @@ fn prestate_tracing_works() {
 					diff_mode: true,
 					disable_storage: false,
 					disable_code: false,
+					storage_filter: None,
 				},
 				PrestateTrace::DiffMode {
 					pre: BTreeMap::from([
@@ -4461,6 +4463,7 @@ fn prestate_tracing_works() {
 					diff_mode: true,
 					disable_storage: false,
 					disable_code: false,
+					storage_filter: None,
 				},
 				PrestateTrace::DiffMode {
 					pre: BTreeMap::from([(
@@ -4524,6 +4527,57 @@ fn prestate_tracing_works() {
 	});
 }
 
+#[test]
+fn prestate_tracing_storage_filter_works() {
+	use crate::{evm::*, tracing::Tracing, ExecReturnValue, U256};
+	use alloc::collections::BTreeMap;
+
+	let traced = H160::repeat_byte(1);
+	let ignored = H160::repeat_byte(2);
+	let slot = |n: u8| Bytes(vec![n; 32]);
+
+	ExtBuilder::default().build().execute_with(|| {
+		let run = |storage_filter| {
+			let mut tracer = PrestateTracer::<Test>::new(PrestateTracerConfig {
+				storage_filter: Some(storage_filter),
+				..Default::default()
+			});
+			for addr in [traced, ignored] {
+				tracer.enter_child_span(
+					ALICE_ADDR,
+					addr,
+					false,
+					false,
+					U256::zero(),
+					&[],
+					Weight::zero(),
+				);
+				tracer.storage_read(&Key::Fix([1; 32]), Some(&[1]));
+				tracer.storage_write(&Key::Fix([2; 32]), Some(vec![2]), Some(&[3]));
+				tracer.exit_child_span(&ExecReturnValue::default(), Weight::zero());
+			}
+
+			let PrestateTrace::Prestate(pre) = tracer.collect_trace() else {
+				panic!("expected a prestate trace");
+			};
+			assert!(pre[&ignored].storage.is_empty());
+			pre[&traced].storage.clone()
+		};
+
+		// An empty list of keys traces all the storage of the contract.
+		assert_eq!(
+			run(BTreeMap::from([(traced, vec![])])),
+			BTreeMap::from([(slot(1), Some(Bytes(vec![1]))), (slot(2), Some(Bytes(vec![2])))]),
+		);
+
+		// Otherwise only the listed keys are traced.
+		assert_eq!(
+			run(BTreeMap::from([(traced, vec![slot(2)])])),
+			BTreeMap::from([(slot(2), Some(Bytes(vec![2])))]),
+		);
+	});
+}
+
 #[test]
 fn unknown_precompiles_revert() {
 	let (code, _code_hash) = compile_module("read_only_call").unwrap();
@@ -23,9 +23,9 @@ use crate::{
 	tests::{
 		builder,
 		test_utils::{contract_base_deposit, ensure_stored, get_contract},
-		DebugFlag, ExtBuilder, Test,
+		DebugFlag, EvmOpcodes, ExtBuilder, Test,
 	},
-	Code, Config, Error, GenesisConfig, PristineCode,
+	Code, Config, Error, GenesisConfig, OpcodePolicy, PristineCode,
 };
 use alloy_core::sol_types::{SolCall, SolInterface};
 use frame_support::{assert_err, assert_ok, traits::fungible::Mutate};
@@ -160,6 +160,56 @@ fn basic_evm_flow_tracing_works() {
 	});
 }
 
+#[test]
+fn selfdestruct_tracing_works() {
+	use crate::{
+		evm::{CallTrace, CallTracer, CallType},
+		test_utils::{ALICE_ADDR, BOB_ADDR},
+		tracing::trace,
+		Pallet,
+	};
+	use pallet_revive_fixtures::{HostEvmOnly, HostEvmOnly::HostEvmOnlyCalls};
+
+	let (code, _) = compile_module_with_type("HostEvmOnly", FixtureType::Solc).unwrap();
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let Contract { addr, account_id } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+		let _ = <Test as Config>::Currency::set_balance(&account_id, 1_000_000);
+		let balance = Pallet::<Test>::evm_balance(&addr);
+
+		let input = HostEvmOnlyCalls::selfdestructOp(HostEvmOnly::selfdestructOpCall {
+			recipient: BOB_ADDR.0.into(),
+		})
+		.abi_encode();
+		let mut tracer = CallTracer::new(Default::default(), |_| crate::U256::zero());
+		trace(&mut tracer, || {
+			builder::bare_call(addr).data(input.clone()).build_and_unwrap_result();
+		});
+
+		assert_eq!(
+			tracer.collect_trace().unwrap(),
+			CallTrace {
+				from: ALICE_ADDR,
+				to: addr,
+				call_type: CallType::Call,
+				input: input.into(),
+				value: Some(crate::U256::zero()),
+				child_call_count: 1,
+				calls: vec![CallTrace {
+					from: addr,
+					to: BOB_ADDR,
+					call_type: CallType::Selfdestruct,
+					value: Some(balance),
+					..Default::default()
+				}],
+				..Default::default()
+			},
+		);
+	});
+}
+
 #[test]
 fn eth_contract_too_large() {
 	// Generate EVM bytecode that is one byte larger than the EIP-3860 limit.
@@ -199,3 +249,42 @@ fn eth_contract_too_large() {
 			});
 	}
 }
+
+#[test]
+fn denied_opcode_halts() {
+	let runtime_code: Vec<u8> = vec![
+		vec![CALLER],
+		vec![PUSH0],
+		vec![MSTORE],
+		vec![PUSH1, 0x20_u8],
+		vec![PUSH0],
+		vec![RETURN],
+	]
+	.into_iter()
+	.flatten()
+	.collect();
+	let code = make_initcode_from_runtime_code(&runtime_code);
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+
+		// The opcode is allowed by default.
+		let result = builder::bare_call(addr).build_and_unwrap_result();
+		assert!(!result.did_revert(), "test reverted");
+
+		// Every call halts once the opcode is denied.
+		EvmOpcodes::set(OpcodePolicy::allow_all().deny(CALLER));
+		for _ in 0..2 {
+			assert_err!(builder::bare_call(addr).build().result, <Error<Test>>::OpcodeDisabled);
+		}
+
+		// Denying an unrelated opcode does not affect the contract.
+		EvmOpcodes::set(OpcodePolicy::allow_all().deny(SELFDESTRUCT));
+		let result = builder::bare_call(addr).build_and_unwrap_result();
+		assert!(!result.did_revert(), "test reverted");
+
+		EvmOpcodes::set(OpcodePolicy::allow_all());
+	});
+}
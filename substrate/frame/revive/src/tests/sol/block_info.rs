@@ -17,18 +17,21 @@
 
 //! The pallet-revive shared VM integration test suite.
 
+use super::make_initcode_from_runtime_code;
 use crate::{
 	test_utils::{builder::Contract, ALICE},
-	tests::{builder, Contracts, ExtBuilder, System, Test, Timestamp},
+	tests::{
+		builder, Contracts, EvmDifficultyMode, EvmRandomSeed, ExtBuilder, System, Test, Timestamp,
+	},
 	vm::evm::DIFFICULTY,
-	Code, Config, Pallet,
+	Code, Config, DifficultyMode, Pallet,
 };
 
 use alloy_core::sol_types::{SolCall, SolInterface};
 use frame_support::traits::fungible::Mutate;
 use pallet_revive_fixtures::{compile_module_with_type, BlockInfo, FixtureType};
 use pretty_assertions::assert_eq;
-use sp_core::H160;
+use sp_core::{H160, H256, U256};
 use test_case::test_case;
 
 /// Tests that the blocknumber opcode works as expected.
@@ -149,6 +152,40 @@ fn base_fee_works(fixture_type: FixtureType) {
 	});
 }
 
+/// Tests that the difficulty opcode returns the value selected by the configured mode.
+#[test]
+fn difficulty_mode_works() {
+	use revm::bytecode::opcode::{DIFFICULTY as DIFFICULTY_OPCODE, MSTORE, PUSH0, PUSH1, RETURN};
+
+	let runtime_code = vec![DIFFICULTY_OPCODE, PUSH0, MSTORE, PUSH1, 0x20_u8, PUSH0, RETURN];
+	let code = make_initcode_from_runtime_code(&runtime_code);
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+		let difficulty = || {
+			let result = builder::bare_call(addr).build_and_unwrap_result();
+			U256::from_big_endian(&result.data)
+		};
+
+		// Constant mode returns the configured value in every block.
+		EvmDifficultyMode::set(DifficultyMode::FixedConstant(42));
+		for block in 1..=2 {
+			System::set_block_number(block);
+			assert_eq!(difficulty(), U256::from(42));
+		}
+
+		// Prevrandao mode returns the output of the randomness source.
+		EvmDifficultyMode::set(DifficultyMode::Prevrandao);
+		EvmRandomSeed::set(H256::repeat_byte(0xab));
+		assert_eq!(difficulty(), U256::from_big_endian(&[0xab; 32]));
+
+		EvmDifficultyMode::set(DifficultyMode::default());
+		EvmRandomSeed::set(H256::zero());
+	});
+}
+
 /// Tests that the difficulty opcode works as expected.
 #[test_case(FixtureType::Solc)]
 #[test_case(FixtureType::Resolc)]
@@ -46,9 +46,9 @@ fn memory_limit_works() {
 				Ok(ExecReturnValue { data: vec![0u8; 32], flags: ReturnFlags::empty() }),
 			),
 			(
-				"Writing 1 byte from the limit should revert.",
+				"Writing 1 byte from the limit should fail.",
 				Memory::expandMemoryCall { memorySize: crate::limits::EVM_MEMORY_BYTES as u64 },
-				Err(Error::<Test>::OutOfGas.into()),
+				Err(Error::<Test>::MemoryLimitExceeded.into()),
 			),
 		];
 
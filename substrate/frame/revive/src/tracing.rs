@@ -76,6 +76,13 @@ pub trait Tracing {
 	) {
 	}
 
+	/// Called when `contract` registers its termination, e.g. by executing `SELFDESTRUCT`.
+	///
+	/// `value` is the balance of the contract at this point, which is transferred to
+	/// `beneficiary` at the end of the transaction. In line with EIP-6780, the contract itself is
+	/// only removed if it was created in the same transaction.
+	fn terminate(&mut self, _contract: H160, _beneficiary: H160, _value: U256) {}
+
 	/// Record a log event
 	fn log_event(&mut self, _event: H160, _topics: &[H256], _data: &[u8]) {}
 
@@ -16,14 +16,18 @@
 // limitations under the License.
 use crate::{
 	debug::DebugSettings,
+	limits,
 	precompiles::Token,
 	vm::{evm::instructions::exec_instruction, BytecodeType, ExecResult, Ext},
 	weights::WeightInfo,
-	AccountIdOf, CodeInfo, Config, ContractBlob, DispatchError, Error, Weight, H256, LOG_TARGET,
+	AccountIdOf, BalanceOf, CodeInfo, Config, ContractBlob, DispatchError, Error, Weight, H256,
+	LOG_TARGET,
 };
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, vec::Vec};
 use core::{convert::Infallible, ops::ControlFlow};
+use frame_support::traits::{Get, Randomness};
 use revm::{bytecode::Bytecode, primitives::Bytes};
+use sp_runtime::traits::Zero;
 
 #[cfg(feature = "runtime-benchmarks")]
 pub mod instructions;
@@ -31,23 +35,93 @@ pub mod instructions;
 mod instructions;
 
 mod interpreter;
-pub use interpreter::{Halt, Interpreter};
+pub use interpreter::{Halt, HaltReason, Interpreter};
 
 mod ext_bytecode;
 use ext_bytecode::ExtBytecode;
 
 mod memory;
+pub use memory::{Memory, MemorySnapshot};
 mod stack;
+pub use stack::Stack;
 mod util;
 
-/// Hard-coded value returned by the EVM `DIFFICULTY` opcode.
+/// Default value returned by the EVM `DIFFICULTY` opcode.
 ///
 /// After Ethereum's Merge (Sept 2022), the `DIFFICULTY` opcode was redefined to return
-/// `prevrandao`, a randomness value from the beacon chain. In Substrate pallet-revive
-/// a fixed constant is returned instead for compatibility with contracts that still read this
-/// opcode. The value is aligned with the difficulty hardcoded for PVM contracts.
+/// `prevrandao`, a randomness value from the beacon chain. By default pallet-revive returns
+/// a fixed constant instead for compatibility with contracts that still read this opcode, see
+/// [`DifficultyMode`]. The value is aligned with the difficulty hardcoded for PVM contracts.
 pub(crate) const DIFFICULTY: u64 = 2500000000000000_u64;
 
+/// Selects the value returned by the EVM `DIFFICULTY`/`PREVRANDAO` opcode.
+///
+/// PVM contracts are not affected, as their compiler hardcodes the difficulty.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DifficultyMode {
+	/// Return the given constant, matching the pre-Merge semantics.
+	FixedConstant(u64),
+	/// Return the output of [`Config::EvmRandomness`](crate::Config::EvmRandomness).
+	Prevrandao,
+}
+
+impl Default for DifficultyMode {
+	fn default() -> Self {
+		Self::FixedConstant(DIFFICULTY)
+	}
+}
+
+/// A [`Randomness`] source for runtimes that never use [`DifficultyMode::Prevrandao`].
+///
+/// Always returns the zero hash.
+pub struct NoRandomness;
+
+impl<BlockNumber: Zero> Randomness<H256, BlockNumber> for NoRandomness {
+	fn random(_subject: &[u8]) -> (H256, BlockNumber) {
+		(H256::zero(), Zero::zero())
+	}
+}
+
+/// A set of EVM opcodes the interpreter refuses to execute.
+///
+/// Executing a denied opcode halts with [`Halt::OpcodeDisabled`]. The [`Default`] policy allows
+/// all opcodes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct OpcodePolicy {
+	/// One bit per opcode, set if the opcode is denied.
+	denied: [u64; 4],
+}
+
+impl OpcodePolicy {
+	/// A policy that allows all opcodes.
+	pub const fn allow_all() -> Self {
+		Self { denied: [0; 4] }
+	}
+
+	/// Returns a copy of this policy with `opcode` denied.
+	pub const fn deny(mut self, opcode: u8) -> Self {
+		self.denied[(opcode / 64) as usize] |= 1 << (opcode % 64);
+		self
+	}
+
+	/// Returns a copy of this policy with `opcode` allowed.
+	pub const fn allow(mut self, opcode: u8) -> Self {
+		self.denied[(opcode / 64) as usize] &= !(1 << (opcode % 64));
+		self
+	}
+
+	/// Returns `true` if no opcode is denied.
+	pub const fn allows_all(&self) -> bool {
+		matches!(self.denied, [0, 0, 0, 0])
+	}
+
+	/// Returns `true` if `opcode` must not be executed.
+	#[inline(always)]
+	pub const fn is_denied(&self, opcode: u8) -> bool {
+		self.denied[(opcode / 64) as usize] & (1 << (opcode % 64)) != 0
+	}
+}
+
 /// Cost  for a single unit of EVM gas.
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub struct EVMGas(u64);
@@ -105,7 +179,7 @@ impl<T: Config> ContractBlob<T> {
 		}
 
 		let code_len = code.len() as u32;
-		let deposit = super::calculate_code_deposit::<T>(code_len);
+		let deposit = Self::estimate_code_deposit(code_len);
 
 		let code_info = CodeInfo {
 			owner,
@@ -124,19 +198,392 @@ impl<T: Config> ContractBlob<T> {
 		let code_hash = H256(sp_io::hashing::keccak_256(&code));
 		Ok(ContractBlob { code, code_info, code_hash })
 	}
+
+	/// Returns the storage deposit charged for storing EVM runtime code of `code_len` bytes.
+	///
+	/// This is exactly the deposit [`Self::from_evm_runtime_code`] records in the code info and
+	/// can be used to preview the deposit before deploying a contract.
+	pub fn estimate_code_deposit(code_len: u32) -> BalanceOf<T> {
+		super::calculate_code_deposit::<T>(code_len)
+	}
+}
+
+/// The analyzed bytecode of the EVM contracts executed in a call stack, keyed by code hash.
+///
+/// Cloning analyzed bytecode is cheap, as the code and its jump table are reference counted. At
+/// most [`limits::EVM_BYTECODE_CACHE_ENTRIES`] contracts of at most
+/// [`eip170::MAX_CODE_SIZE`](revm::primitives::eip170::MAX_CODE_SIZE) bytes are cached, which is
+/// accounted for in [`limits::MEMORY_REQUIRED`]. Other contracts are analyzed on every call.
+#[derive(Default)]
+pub(crate) struct BytecodeCache(BTreeMap<H256, Bytecode>);
+
+impl BytecodeCache {
+	/// Returns the analyzed bytecode of `code_hash`, analyzing the result of `code` if it is not
+	/// cached.
+	pub(crate) fn get_or_analyze(
+		&mut self,
+		code_hash: H256,
+		code: impl FnOnce() -> Vec<u8>,
+	) -> Bytecode {
+		if let Some(bytecode) = self.0.get(&code_hash) {
+			return bytecode.clone();
+		}
+
+		let code = code();
+		let cacheable = self.0.len() < limits::EVM_BYTECODE_CACHE_ENTRIES as usize &&
+			code.len() <= revm::primitives::eip170::MAX_CODE_SIZE;
+		let bytecode = Bytecode::new_raw(code.into());
+		if cacheable {
+			self.0.insert(code_hash, bytecode.clone());
+		}
+		bytecode
+	}
+}
+
+/// The gas consumed by an EVM call frame, split by opcode category.
+///
+/// The gas of the opcodes that call or create another contract includes the gas consumed by
+/// the callee. The counters are in [`Weight`], as this is what the interpreter meters. The EVM gas
+/// reported to Ethereum clients is derived from the fee of the whole transaction, which depends
+/// on the runtime and has no fixed rate per unit of weight.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct GasProfile {
+	/// Arithmetic, comparison and bitwise opcodes.
+	pub arithmetic: Weight,
+	/// Storage and transient storage opcodes.
+	pub storage: Weight,
+	/// Opcodes that load from, store to or copy into memory.
+	pub memory: Weight,
+	/// Opcodes that call or create another contract.
+	pub call: Weight,
+	/// All other opcodes.
+	pub other: Weight,
+}
+
+/// Observes the weight consumed by every opcode executed in an EVM call frame.
+trait Profiler {
+	/// Whether [`Self::record`] is called. If not, the gas meter is not read around every opcode.
+	const ENABLED: bool;
+
+	/// Attribute `consumed` to `opcode`.
+	fn record(&mut self, opcode: u8, consumed: Weight);
+}
+
+/// Records nothing, so profiling compiles out of the interpreter loop.
+impl Profiler for () {
+	const ENABLED: bool = false;
+
+	fn record(&mut self, _opcode: u8, _consumed: Weight) {}
+}
+
+impl Profiler for GasProfile {
+	const ENABLED: bool = true;
+
+	fn record(&mut self, opcode: u8, consumed: Weight) {
+		use revm::bytecode::opcode::*;
+
+		let counter = match opcode {
+			ADD..=SIGNEXTEND | LT..=SAR => &mut self.arithmetic,
+			SLOAD | SSTORE | TLOAD | TSTORE => &mut self.storage,
+			MLOAD | MSTORE | MSTORE8 | MCOPY | CALLDATACOPY | CODECOPY | EXTCODECOPY |
+			RETURNDATACOPY => &mut self.memory,
+			CREATE | CALL | CALLCODE | DELEGATECALL | CREATE2 | STATICCALL => &mut self.call,
+			_ => &mut self.other,
+		};
+		*counter = counter.saturating_add(consumed);
+	}
 }
 
 /// Calls the EVM interpreter with the provided bytecode and inputs.
 pub fn call<E: Ext>(bytecode: Bytecode, ext: &mut E, input: Vec<u8>) -> ExecResult {
+	run(bytecode, ext, input, &mut ())
+}
+
+/// Same as [`call`], but takes a reference to bytecode that was already analyzed by
+/// [`Bytecode::new_raw`].
+///
+/// Cloning analyzed bytecode is cheap, as the code and its jump table are reference counted. This
+/// allows reusing the analysis when the same contract is called repeatedly.
+pub fn call_analyzed<E: Ext>(bytecode: &Bytecode, ext: &mut E, input: Vec<u8>) -> ExecResult {
+	run(bytecode.clone(), ext, input, &mut ())
+}
+
+/// Same as [`call`], but also returns the [`GasProfile`] of the call frame.
+///
+/// Profiling reads the gas meter around every opcode, which is why [`call`] does not do it.
+pub fn call_with_profile<E: Ext>(
+	bytecode: Bytecode,
+	ext: &mut E,
+	input: Vec<u8>,
+) -> (ExecResult, GasProfile) {
+	let mut profile = GasProfile::default();
+	let result = run(bytecode, ext, input, &mut profile);
+	(result, profile)
+}
+
+fn run<E: Ext, P: Profiler>(
+	bytecode: Bytecode,
+	ext: &mut E,
+	input: Vec<u8>,
+	profiler: &mut P,
+) -> ExecResult {
 	let mut interpreter = Interpreter::new(ExtBytecode::new(bytecode), input, ext);
-	let ControlFlow::Break(halt) = run_plain(&mut interpreter);
-	halt.into()
+	let policy = <E::T as Config>::EvmOpcodePolicy::get();
+	let ControlFlow::Break(halt) = if policy.allows_all() {
+		run_plain::<_, false, _>(&mut interpreter, &policy, profiler)
+	} else {
+		run_plain::<_, true, _>(&mut interpreter, &policy, profiler)
+	};
+	halt.into_exec_result::<E::T>()
 }
 
-fn run_plain<E: Ext>(interpreter: &mut Interpreter<E>) -> ControlFlow<Halt, Infallible> {
+/// Executes opcodes until the interpreter halts.
+///
+/// revm disables opcodes by replacing their entries in its instruction table. This interpreter
+/// dispatches through [`exec_instruction`] instead, so `policy` has to be checked before every
+/// opcode. The check is only compiled in if `CHECK_POLICY` is set, which [`run`] only does if
+/// the policy denies any opcode.
+fn run_plain<E: Ext, const CHECK_POLICY: bool, P: Profiler>(
+	interpreter: &mut Interpreter<E>,
+	policy: &OpcodePolicy,
+	profiler: &mut P,
+) -> ControlFlow<Halt, Infallible> {
 	loop {
 		let opcode = interpreter.bytecode.opcode();
 		interpreter.bytecode.relative_jump(1);
-		exec_instruction(interpreter, opcode)?;
+		if CHECK_POLICY && policy.is_denied(opcode) {
+			return ControlFlow::Break(Halt::OpcodeDisabled(opcode));
+		}
+		if !P::ENABLED {
+			exec_instruction(interpreter, opcode)?;
+			continue;
+		}
+		let gas_left = interpreter.ext.gas_meter().gas_left();
+		let result = exec_instruction(interpreter, opcode);
+		profiler.record(opcode, gas_left.saturating_sub(interpreter.ext.gas_meter().gas_left()));
+		result?;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		exec::{mock_ext::MockExt, Executable, Key, PrecompileExt},
+		test_utils::ALICE,
+		tests::{EvmReturnDataLimit, ExtBuilder, Test},
+		U256,
+	};
+	use revm::bytecode::opcode::{
+		ADD, CREATE2, DELEGATECALL, JUMP, MCOPY, MSTORE, PUSH1, PUSH2, PUSH3, RETURN, REVERT,
+		SELFDESTRUCT, SLOAD, SSTORE, STOP,
+	};
+
+	#[test]
+	fn stack_can_be_inspected_after_execution() {
+		let code = vec![PUSH1, 1, PUSH1, 2, PUSH1, 3, ADD, STOP];
+		let mut mock_ext = MockExt::<Test>::new();
+		let mut interpreter = Interpreter::new(
+			ExtBytecode::new(Bytecode::new_raw(code.into())),
+			vec![],
+			&mut mock_ext,
+		);
+
+		let ControlFlow::Break(halt) =
+			run_plain::<_, false, _>(&mut interpreter, &OpcodePolicy::allow_all(), &mut ());
+		assert_eq!(halt, Halt::Stop);
+
+		let stack = &interpreter.stack;
+		assert_eq!(stack.len(), 2);
+		assert_eq!(stack.peek(0), Some(&U256::from(5)));
+		assert_eq!(stack.peek(1), Some(&U256::from(1)));
+		assert_eq!(stack.peek(2), None);
+		assert_eq!(stack.top(), stack.peek(0));
+		assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![U256::from(5), U256::from(1)]);
+	}
+
+	#[test]
+	fn jump_to_non_jumpdest_halts_with_invalid_jump() {
+		// Offset 3 is a `STOP`, not a `JUMPDEST`.
+		let code = vec![PUSH1, 3, JUMP, STOP];
+		let mut mock_ext = MockExt::<Test>::new();
+		let mut interpreter = Interpreter::new(
+			ExtBytecode::new(Bytecode::new_raw(code.into())),
+			vec![],
+			&mut mock_ext,
+		);
+
+		let ControlFlow::Break(halt) =
+			run_plain::<_, false, _>(&mut interpreter, &OpcodePolicy::allow_all(), &mut ());
+		assert_eq!(halt, Halt::InvalidJump);
+		assert_eq!(halt.reason(), HaltReason::InvalidJump);
+		assert_eq!(halt.into_exec_result::<Test>(), Err(Error::<Test>::InvalidJump.into()));
+	}
+
+	#[test]
+	fn gas_profile_attributes_gas_to_opcode_categories() {
+		// Copy 4096 bytes within memory, then add two numbers.
+		let code =
+			vec![PUSH2, 0x10, 0x00, PUSH1, 0, PUSH1, 0, MCOPY, PUSH1, 1, PUSH1, 2, ADD, STOP];
+		let mut mock_ext = MockExt::<Test>::new();
+
+		let (result, profile) =
+			call_with_profile(Bytecode::new_raw(code.into()), &mut mock_ext, vec![]);
+		assert_eq!(result, Ok(Default::default()));
+
+		assert!(profile.memory.any_gt(profile.arithmetic + profile.other));
+		assert!(!profile.arithmetic.is_zero());
+		assert!(!profile.other.is_zero());
+		assert!(profile.storage.is_zero());
+		assert!(profile.call.is_zero());
+		assert_eq!(
+			profile.arithmetic + profile.storage + profile.memory + profile.call + profile.other,
+			Weight::MAX - mock_ext.gas_meter().gas_left(),
+		);
+	}
+
+	#[test]
+	fn gas_profile_attributes_storage_access() {
+		// Store `1` at key 0, then copy it to keys 1 and 2 through SLOAD and SSTORE.
+		let code = vec![
+			PUSH1, 1, PUSH1, 0, SSTORE, PUSH1, 0, SLOAD, PUSH1, 1, SSTORE, PUSH1, 1, SLOAD, PUSH1,
+			2, SSTORE, STOP,
+		];
+		let mut mock_ext = MockExt::<Test>::new();
+
+		let (result, profile) =
+			call_with_profile(Bytecode::new_raw(code.into()), &mut mock_ext, vec![]);
+		assert_eq!(result, Ok(Default::default()));
+		for key in 0..3 {
+			assert_eq!(
+				mock_ext.get_storage(&Key::Fix(U256::from(key).to_big_endian())),
+				Some(U256::one().to_big_endian().to_vec()),
+			);
+		}
+
+		let largest_other = [profile.arithmetic, profile.memory, profile.call, profile.other]
+			.into_iter()
+			.map(|weight| weight.ref_time())
+			.max()
+			.unwrap();
+		assert!(profile.storage.ref_time() > largest_other);
+		assert_eq!(
+			profile.arithmetic + profile.storage + profile.memory + profile.call + profile.other,
+			Weight::MAX - mock_ext.gas_meter().gas_left(),
+		);
+	}
+
+	#[test]
+	fn call_analyzed_reuses_bytecode() {
+		// Return the 32 byte word `42`.
+		let code = vec![PUSH1, 42, PUSH1, 0, MSTORE, PUSH1, 32, PUSH1, 0, RETURN];
+		let bytecode = Bytecode::new_raw(code.into());
+		let mut mock_ext = MockExt::<Test>::new();
+
+		for _ in 0..2 {
+			let output = call_analyzed(&bytecode, &mut mock_ext, vec![]).unwrap();
+			assert_eq!(U256::from_big_endian(&output.data), U256::from(42));
+		}
+	}
+
+	#[test]
+	fn return_data_limit_works() {
+		let run = |opcode: u8, len: u32| {
+			let [_, b0, b1, b2] = len.to_be_bytes();
+			let code = vec![PUSH3, b0, b1, b2, PUSH1, 0, opcode];
+			let mut mock_ext = MockExt::<Test>::new();
+			let mut interpreter = Interpreter::new(
+				ExtBytecode::new(Bytecode::new_raw(code.into())),
+				vec![],
+				&mut mock_ext,
+			);
+			let ControlFlow::Break(halt) =
+				run_plain::<_, false, _>(&mut interpreter, &OpcodePolicy::allow_all(), &mut ());
+			halt
+		};
+
+		EvmReturnDataLimit::set(1024);
+
+		// Just under the limit succeeds.
+		assert_eq!(run(RETURN, 1024), Halt::Return(vec![0; 1024]));
+		assert_eq!(run(REVERT, 1024), Halt::Revert(vec![0; 1024]));
+
+		// An oversized buffer halts without being copied.
+		for opcode in [RETURN, REVERT] {
+			let halt = run(opcode, 1025);
+			assert_eq!(halt, Halt::ReturnDataTooLarge);
+			assert_eq!(
+				halt.into_exec_result::<Test>(),
+				Err(Error::<Test>::ReturnDataTooLarge.into())
+			);
+		}
+
+		// The configured limit can never raise the hard limit.
+		EvmReturnDataLimit::set(u32::MAX);
+		assert_eq!(run(RETURN, limits::CALLDATA_BYTES).reason(), HaltReason::Return);
+		assert_eq!(run(RETURN, limits::CALLDATA_BYTES + 1), Halt::ReturnDataTooLarge);
+
+		EvmReturnDataLimit::set(limits::CALLDATA_BYTES);
+	}
+
+	#[test]
+	fn estimate_code_deposit_matches_stored_deposit() {
+		ExtBuilder::default().build().execute_with(|| {
+			for code_len in [0, 1, 100, 4096, revm::primitives::eip170::MAX_CODE_SIZE] {
+				let blob = ContractBlob::<Test>::from_evm_runtime_code(vec![STOP; code_len], ALICE)
+					.unwrap();
+				assert_eq!(
+					ContractBlob::<Test>::estimate_code_deposit(code_len as u32),
+					blob.code_info().deposit(),
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn opcode_policy_works() {
+		let policy = OpcodePolicy::allow_all().deny(SELFDESTRUCT).deny(CREATE2).deny(DELEGATECALL);
+		assert!(policy.is_denied(SELFDESTRUCT));
+		assert!(policy.is_denied(CREATE2));
+		assert!(policy.is_denied(DELEGATECALL));
+		assert!(!policy.is_denied(STOP));
+		assert_eq!((0..=u8::MAX).filter(|op| policy.is_denied(*op)).count(), 3);
+
+		let policy = policy.allow(CREATE2);
+		assert!(!policy.is_denied(CREATE2));
+		assert!(!policy.allows_all());
+		assert!(policy.allow(SELFDESTRUCT).allow(DELEGATECALL).allows_all());
+		assert_eq!(OpcodePolicy::default(), OpcodePolicy::allow_all());
+	}
+
+	#[test]
+	fn bytecode_cache_is_bounded() {
+		let analyzed = core::cell::Cell::new(0);
+		let mut cache = BytecodeCache::default();
+		let mut get = |code_hash: u8, len: usize| {
+			cache.get_or_analyze(H256::repeat_byte(code_hash), || {
+				analyzed.set(analyzed.get() + 1);
+				vec![STOP; len]
+			})
+		};
+
+		assert_eq!(get(0, 10), Bytecode::new_raw(vec![STOP; 10].into()));
+		assert_eq!(get(0, 10), Bytecode::new_raw(vec![STOP; 10].into()));
+		assert_eq!(analyzed.get(), 1);
+
+		// Contracts bigger than the maximum code size are not cached.
+		get(1, revm::primitives::eip170::MAX_CODE_SIZE + 1);
+		get(1, revm::primitives::eip170::MAX_CODE_SIZE + 1);
+		assert_eq!(analyzed.get(), 3);
+
+		// Once the cache is full, new contracts are analyzed on every call.
+		for code_hash in 2..=limits::EVM_BYTECODE_CACHE_ENTRIES as u8 {
+			get(code_hash, 10);
+		}
+		assert_eq!(analyzed.get(), 2 + limits::EVM_BYTECODE_CACHE_ENTRIES);
+		get(u8::MAX, 10);
+		get(u8::MAX, 10);
+		get(0, 10);
+		assert_eq!(analyzed.get(), 4 + limits::EVM_BYTECODE_CACHE_ENTRIES);
 	}
 }
@@ -17,12 +17,13 @@
 
 use crate::{
 	vm::{
-		evm::{interpreter::Halt, EVMGas, Interpreter, DIFFICULTY},
+		evm::{interpreter::Halt, DifficultyMode, EVMGas, Interpreter},
 		Ext,
 	},
-	Error, RuntimeCosts,
+	Config, Error, RuntimeCosts,
 };
 use core::ops::ControlFlow;
+use frame_support::traits::{Get, Randomness};
 use revm::interpreter::gas::BASE;
 use sp_core::U256;
 
@@ -65,10 +66,21 @@ pub fn block_number<E: Ext>(interpreter: &mut Interpreter<E>) -> ControlFlow<Hal
 
 /// Implements the DIFFICULTY/PREVRANDAO instruction.
 ///
-/// Pushes the block difficulty (pre-merge) or prevrandao (post-merge) onto the stack.
+/// Pushes the block difficulty (pre-merge) or prevrandao (post-merge) onto the stack, as selected
+/// by [`Config::EvmDifficulty`].
 pub fn difficulty<E: Ext>(interpreter: &mut Interpreter<E>) -> ControlFlow<Halt> {
 	interpreter.ext.charge_or_halt(EVMGas(BASE))?;
-	interpreter.stack.push(U256::from(DIFFICULTY))?;
+	let difficulty = match <E::T as Config>::EvmDifficulty::get() {
+		DifficultyMode::FixedConstant(difficulty) => U256::from(difficulty),
+		DifficultyMode::Prevrandao => {
+			// There is no benchmark for reading the randomness yet. It is charged like looking
+			// up a block hash until weights for it are generated.
+			interpreter.ext.charge_or_halt(RuntimeCosts::BlockHash)?;
+			let (seed, _) = <E::T as Config>::EvmRandomness::random(b"prevrandao");
+			U256::from_big_endian(seed.as_bytes())
+		},
+	};
+	interpreter.stack.push(difficulty)?;
 	ControlFlow::Continue(())
 }
 
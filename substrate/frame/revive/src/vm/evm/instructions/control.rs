@@ -16,6 +16,7 @@
 // limitations under the License.
 
 use crate::{
+	limits,
 	vm::{
 		evm::{
 			interpreter::Halt,
@@ -24,10 +25,11 @@ use crate::{
 		},
 		Ext,
 	},
-	Error, U256,
+	Config, Error, U256,
 };
 use alloc::vec::Vec;
 use core::ops::ControlFlow;
+use frame_support::traits::Get;
 use revm::interpreter::gas::{BASE, HIGH, JUMPDEST, MID};
 
 /// Implements the JUMP instruction.
@@ -58,10 +60,10 @@ pub fn jumpi<E: Ext>(interpreter: &mut Interpreter<E>) -> ControlFlow<Halt> {
 ///
 /// Validates jump target and performs the actual jump.
 fn jump_inner<E: Ext>(interpreter: &mut Interpreter<E>, target: U256) -> ControlFlow<Halt> {
-	let target = as_usize_or_halt_with(target, || Error::<E::T>::InvalidJump.into())?;
+	let target = as_usize_or_halt_with(target, || Halt::InvalidJump)?;
 
 	if !interpreter.bytecode.is_valid_legacy_jump(target) {
-		return ControlFlow::Break(Error::<E::T>::InvalidJump.into());
+		return ControlFlow::Break(Halt::InvalidJump);
 	}
 	// SAFETY: `is_valid_jump` ensures that `dest` is in bounds.
 	interpreter.bytecode.absolute_jump(target);
@@ -96,6 +98,9 @@ fn return_inner<E: Ext>(
 ) -> ControlFlow<Halt> {
 	let [offset, len] = interpreter.stack.popn()?;
 	let len = as_usize_or_halt::<E::T>(len)?;
+	if len > return_data_limit::<E::T>() {
+		return ControlFlow::Break(Halt::ReturnDataTooLarge);
+	}
 
 	// Important: Offset must be ignored if len is zeros
 	let mut output = Default::default();
@@ -108,6 +113,13 @@ fn return_inner<E: Ext>(
 	ControlFlow::Break(halt(output))
 }
 
+/// The maximum number of bytes `RETURN` and `REVERT` may output.
+///
+/// This is [`Config::EvmReturnDataLimit`] capped at [`limits::CALLDATA_BYTES`].
+fn return_data_limit<T: Config>() -> usize {
+	T::EvmReturnDataLimit::get().min(limits::CALLDATA_BYTES) as usize
+}
+
 /// Implements the RETURN instruction.
 ///
 /// Halts execution and returns data from memory.
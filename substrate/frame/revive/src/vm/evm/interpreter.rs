@@ -22,10 +22,11 @@ use crate::{
 		evm::{memory::Memory, stack::Stack},
 		ExecResult, Ext,
 	},
-	Config, DispatchError, Error,
+	Config, DispatchError, Error, LOG_TARGET,
 };
 use alloc::vec::Vec;
 use pallet_revive_uapi::ReturnFlags;
+use sp_runtime::ModuleError;
 
 /// EVM execution halt - either successful termination or error
 #[derive(Debug, PartialEq)]
@@ -34,25 +35,148 @@ pub enum Halt {
 	Return(Vec<u8>),
 	Revert(Vec<u8>),
 	Err(DispatchError),
+	/// The opcode is denied by [`Config::EvmOpcodePolicy`].
+	OpcodeDisabled(u8),
+	/// The memory grew beyond [`Config::EvmMemoryLimit`].
+	MemoryLimitExceeded,
+	/// A jump to a destination that is not a `JUMPDEST`.
+	InvalidJump,
+	/// The data passed to `RETURN` or `REVERT` exceeds [`Config::EvmReturnDataLimit`].
+	ReturnDataTooLarge,
 }
 
-impl<T: Config> From<Error<T>> for Halt {
-	fn from(err: Error<T>) -> Self {
-		Halt::Err(err.into())
+impl Halt {
+	/// Classify this halt into a [`HaltReason`].
+	///
+	/// Pallet errors are classified by their name, the same way the call tracer reports them.
+	pub fn reason(&self) -> HaltReason {
+		let err = match self {
+			Halt::Stop => return HaltReason::Stop,
+			Halt::Return(_) => return HaltReason::Return,
+			Halt::Revert(_) => return HaltReason::Revert,
+			Halt::OpcodeDisabled(_) => return HaltReason::InvalidOpcode,
+			Halt::MemoryLimitExceeded => return HaltReason::OutOfGas,
+			Halt::InvalidJump => return HaltReason::InvalidJump,
+			Halt::ReturnDataTooLarge => return HaltReason::Other,
+			Halt::Err(err) => err,
+		};
+
+		let DispatchError::Module(ModuleError { message: Some(name), .. }) = err else {
+			return HaltReason::Other
+		};
+		match *name {
+			"OutOfGas" => HaltReason::OutOfGas,
+			"InvalidInstruction" => HaltReason::InvalidOpcode,
+			"StackUnderflow" => HaltReason::StackUnderflow,
+			"StackOverflow" => HaltReason::StackOverflow,
+			"StateChangeDenied" => HaltReason::WriteProtection,
+			_ => HaltReason::Other,
+		}
+	}
+
+	/// Returns the geth-style error string for this halt.
+	///
+	/// See [`HaltReason::as_rpc_error_string`].
+	pub fn as_rpc_error_string(&self) -> &'static str {
+		self.reason().as_rpc_error_string()
 	}
-}
 
-impl From<Halt> for ExecResult {
-	fn from(halt: Halt) -> Self {
-		match halt {
+	/// Convert this halt into the result of the contract execution.
+	pub fn into_exec_result<T: Config>(self) -> ExecResult {
+		match self {
 			Halt::Stop => Ok(ExecReturnValue::default()),
 			Halt::Return(data) => Ok(ExecReturnValue { flags: ReturnFlags::empty(), data }),
 			Halt::Revert(data) => Ok(ExecReturnValue { flags: ReturnFlags::REVERT, data }),
 			Halt::Err(err) => Err(err.into()),
+			Halt::OpcodeDisabled(opcode) => {
+				log::debug!(target: LOG_TARGET, "evm opcode {opcode:#04x} is disabled");
+				Err(Error::<T>::OpcodeDisabled.into())
+			},
+			Halt::MemoryLimitExceeded => Err(Error::<T>::MemoryLimitExceeded.into()),
+			Halt::InvalidJump => Err(Error::<T>::InvalidJump.into()),
+			Halt::ReturnDataTooLarge => Err(Error::<T>::ReturnDataTooLarge.into()),
+		}
+	}
+}
+
+/// The reason why the EVM execution halted.
+///
+/// This is a flat discriminant of [`Halt`] that also classifies the errors raised by the
+/// interpreter, so that they can be reported consistently to eth-RPC clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HaltReason {
+	/// Execution stopped via `STOP`.
+	Stop,
+	/// Execution returned via `RETURN`.
+	Return,
+	/// Execution reverted via `REVERT`.
+	Revert,
+	/// Execution ran out of gas.
+	OutOfGas,
+	/// An undefined or `INVALID` opcode was executed.
+	InvalidOpcode,
+	/// A jump to a destination that is not a `JUMPDEST`.
+	InvalidJump,
+	/// An opcode tried to pop more items than available on the stack.
+	StackUnderflow,
+	/// The stack exceeded its maximum depth.
+	StackOverflow,
+	/// A state changing opcode was executed in a static context.
+	WriteProtection,
+	/// Any other error.
+	///
+	/// This includes the errors geth does not have, e.g. [`Halt::ReturnDataTooLarge`].
+	Other,
+}
+
+impl HaltReason {
+	/// All the variants of [`HaltReason`].
+	pub const ALL: [HaltReason; 10] = [
+		HaltReason::Stop,
+		HaltReason::Return,
+		HaltReason::Revert,
+		HaltReason::OutOfGas,
+		HaltReason::InvalidOpcode,
+		HaltReason::InvalidJump,
+		HaltReason::StackUnderflow,
+		HaltReason::StackOverflow,
+		HaltReason::WriteProtection,
+		HaltReason::Other,
+	];
+
+	/// Returns the error string geth reports for this halt reason.
+	///
+	/// The strings are part of the eth-RPC surface and must not change. They differ from geth in
+	/// three ways:
+	///
+	/// - geth reports no error for a successful execution. [`HaltReason::Stop`] and
+	///   [`HaltReason::Return`] are reported as `stopped` and `returned`, so every reason has a
+	///   string. Callers should not report them as an error.
+	/// - geth appends details to some errors, e.g. `invalid opcode: SELFDESTRUCT` or `stack
+	///   underflow (0 <=> 1)`. Only the fixed prefix is returned, as the details are not known.
+	/// - [`HaltReason::Other`] has no geth counterpart and is reported as `execution failed`.
+	pub fn as_rpc_error_string(&self) -> &'static str {
+		match self {
+			HaltReason::Stop => "stopped",
+			HaltReason::Return => "returned",
+			HaltReason::Revert => "execution reverted",
+			HaltReason::OutOfGas => "out of gas",
+			HaltReason::InvalidOpcode => "invalid opcode",
+			HaltReason::InvalidJump => "invalid jump destination",
+			HaltReason::StackUnderflow => "stack underflow",
+			HaltReason::StackOverflow => "stack limit reached",
+			HaltReason::WriteProtection => "write protection",
+			HaltReason::Other => "execution failed",
 		}
 	}
 }
 
+impl<T: Config> From<Error<T>> for Halt {
+	fn from(err: Error<T>) -> Self {
+		Halt::Err(err.into())
+	}
+}
+
 /// EVM interpreter state using sp_core types
 #[derive(Debug)]
 pub struct Interpreter<'a, E: Ext> {
@@ -74,3 +198,45 @@ impl<'a, E: Ext> Interpreter<'a, E> {
 		Self { ext, bytecode, input, stack: Stack::new(), memory: Memory::new() }
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::Test;
+
+	#[test]
+	fn halt_reason_maps_to_stable_rpc_error_string() {
+		let halts = [
+			(Halt::Stop, HaltReason::Stop),
+			(Halt::Return(Vec::new()), HaltReason::Return),
+			(Halt::Revert(Vec::new()), HaltReason::Revert),
+			(Error::<Test>::OutOfGas.into(), HaltReason::OutOfGas),
+			(Error::<Test>::InvalidInstruction.into(), HaltReason::InvalidOpcode),
+			(Halt::InvalidJump, HaltReason::InvalidJump),
+			(Error::<Test>::StackUnderflow.into(), HaltReason::StackUnderflow),
+			(Error::<Test>::StackOverflow.into(), HaltReason::StackOverflow),
+			(Error::<Test>::StateChangeDenied.into(), HaltReason::WriteProtection),
+			(Error::<Test>::ContractTrapped.into(), HaltReason::Other),
+			(Halt::OpcodeDisabled(0xff), HaltReason::InvalidOpcode),
+			(Halt::MemoryLimitExceeded, HaltReason::OutOfGas),
+			(Halt::ReturnDataTooLarge, HaltReason::Other),
+		];
+
+		for (halt, reason) in halts.iter() {
+			assert_eq!(halt.reason(), *reason);
+			assert_eq!(halt.as_rpc_error_string(), reason.as_rpc_error_string());
+		}
+
+		let strings: Vec<_> = HaltReason::ALL.iter().map(|r| r.as_rpc_error_string()).collect();
+		assert!(strings.iter().all(|s| !s.is_empty()));
+		for (i, s) in strings.iter().enumerate() {
+			assert!(!strings[i + 1..].contains(s), "duplicate rpc error string: {s}");
+		}
+
+		assert_eq!(HaltReason::OutOfGas.as_rpc_error_string(), "out of gas");
+		assert_eq!(HaltReason::InvalidOpcode.as_rpc_error_string(), "invalid opcode");
+		assert_eq!(HaltReason::StackUnderflow.as_rpc_error_string(), "stack underflow");
+		assert_eq!(HaltReason::StackOverflow.as_rpc_error_string(), "stack limit reached");
+		assert_eq!(HaltReason::Revert.as_rpc_error_string(), "execution reverted");
+	}
+}
@@ -15,9 +15,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{vm::evm::Halt, Config, Error};
+use crate::{vm::evm::Halt, Config};
 use alloc::vec::Vec;
 use core::ops::{ControlFlow, Range};
+use frame_support::traits::Get;
+
+/// A copy of the contents of a [`Memory`], taken by [`Memory::snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySnapshot(Vec<u8>);
 
 /// EVM memory implementation
 #[derive(Debug, Clone)]
@@ -58,13 +63,20 @@ impl<T: Config> Memory<T> {
 		self.data.len()
 	}
 
+	/// The maximum size in bytes the memory is allowed to grow to.
+	///
+	/// This is [`Config::EvmMemoryLimit`] capped at [`crate::limits::EVM_MEMORY_BYTES`].
+	pub fn limit() -> usize {
+		T::EvmMemoryLimit::get().min(crate::limits::EVM_MEMORY_BYTES) as usize
+	}
+
 	/// Resize memory to accommodate the given offset and length
 	pub fn resize(&mut self, offset: usize, len: usize) -> ControlFlow<Halt> {
 		let current_len = self.data.len();
 		let target_len = revm::interpreter::num_words(offset.saturating_add(len)) * 32;
-		if target_len > crate::limits::EVM_MEMORY_BYTES as usize {
+		if target_len > Self::limit() {
 			log::debug!(target: crate::LOG_TARGET, "check memory bounds failed: offset={offset} target_len={target_len} current_len={current_len}");
-			return ControlFlow::Break(Error::<T>::OutOfGas.into());
+			return ControlFlow::Break(Halt::MemoryLimitExceeded);
 		}
 
 		if target_len > current_len {
@@ -118,6 +130,22 @@ impl<T: Config> Memory<T> {
 		&self.data[offset..offset + size]
 	}
 
+	/// Take a snapshot of the memory contents.
+	///
+	/// This copies the memory, which is bounded by [`Self::limit`]. It is independent of the
+	/// storage overlay: restoring the snapshot only reverts the memory.
+	pub fn snapshot(&self) -> MemorySnapshot {
+		MemorySnapshot(self.data.clone())
+	}
+
+	/// Restore the memory contents from a `snapshot`.
+	///
+	/// All writes since the snapshot was taken are discarded and the memory shrinks back to the
+	/// size it had at that point.
+	pub fn restore(&mut self, snapshot: MemorySnapshot) {
+		self.data = snapshot.0;
+	}
+
 	/// Copy data within memory from src to dst
 	///
 	/// # Panics
@@ -131,7 +159,7 @@ impl<T: Config> Memory<T> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::tests::Test;
+	use crate::tests::{EvmMemoryLimit, Test};
 
 	#[test]
 	fn test_memory_resize() {
@@ -173,6 +201,22 @@ mod tests {
 		assert_eq!(memory.slice(0..5), b"Hello"); // Original should still be there
 	}
 
+	#[test]
+	fn test_snapshot_restore() {
+		let mut memory = Memory::<Test>::new();
+		assert!(memory.resize(0, 32).is_continue());
+		memory.set(0, b"Hello");
+
+		let snapshot = memory.snapshot();
+		memory.set(0, b"World");
+		assert!(memory.resize(0, 64).is_continue());
+		assert_eq!(memory.size(), 64);
+
+		memory.restore(snapshot);
+		assert_eq!(memory.size(), 32);
+		assert_eq!(memory.slice(0..5), b"Hello");
+	}
+
 	#[test]
 	fn test_set_data() {
 		let mut memory = Memory::<Test>::new();
@@ -186,4 +230,29 @@ mod tests {
 		memory.set_data(15, 6, 5, source_data);
 		assert_eq!(memory.slice(15..20), b"World");
 	}
+
+	#[test]
+	fn test_memory_limit() {
+		let mut memory = Memory::<Test>::new();
+		assert_eq!(Memory::<Test>::limit(), crate::limits::EVM_MEMORY_BYTES as usize);
+
+		// A huge offset halts on the ceiling without allocating.
+		assert_eq!(
+			memory.resize(usize::MAX / 2, 32),
+			ControlFlow::Break(Halt::MemoryLimitExceeded)
+		);
+		assert_eq!(memory.size(), 0);
+
+		// The configured limit can lower the ceiling.
+		EvmMemoryLimit::set(1024);
+		assert!(memory.resize(0, 1024).is_continue());
+		assert_eq!(memory.resize(1024, 1), ControlFlow::Break(Halt::MemoryLimitExceeded));
+		assert_eq!(memory.size(), 1024);
+
+		// But it can never raise it above the hard limit.
+		EvmMemoryLimit::set(u32::MAX);
+		assert_eq!(Memory::<Test>::limit(), crate::limits::EVM_MEMORY_BYTES as usize);
+
+		EvmMemoryLimit::set(crate::limits::EVM_MEMORY_BYTES);
+	}
 }
@@ -70,18 +70,26 @@ impl<T: Config> Stack<T> {
 	}
 
 	/// Get a reference to the top stack item without removing it
-	#[cfg(test)]
 	pub fn top(&self) -> Option<&U256> {
 		self.stack.last()
 	}
 
+	/// Get a reference to the `n`th item from the top without removing it, `0` being the top.
+	pub fn peek(&self, n: usize) -> Option<&U256> {
+		self.stack.iter().rev().nth(n)
+	}
+
+	/// Iterate over the stack items from the top to the bottom.
+	pub fn iter(&self) -> impl Iterator<Item = &U256> {
+		self.stack.iter().rev()
+	}
+
 	/// Get the current stack size
 	pub fn len(&self) -> usize {
 		self.stack.len()
 	}
 
 	/// Check if stack is empty
-	#[cfg(test)]
 	pub fn is_empty(&self) -> bool {
 		self.stack.is_empty()
 	}
@@ -25,6 +25,7 @@ mod runtime_costs;
 pub use runtime_costs::RuntimeCosts;
 
 use crate::{
+	address,
 	exec::{ExecResult, Executable, ExportedFunction, Ext},
 	frame_support::{ensure, error::BadOrigin, traits::tokens::Restriction},
 	gas::{GasMeter, Token},
@@ -43,7 +44,7 @@ use frame_support::{
 	},
 };
 use pallet_revive_uapi::ReturnErrorCode;
-use sp_core::{Get, H256};
+use sp_core::{Get, H160, H256};
 use sp_runtime::{DispatchError, Saturating};
 
 /// Validated Vm module ready for execution.
@@ -163,6 +164,22 @@ pub fn code_load_weight(code_len: u32) -> Weight {
 }
 
 impl<T: Config> ContractBlob<T> {
+	/// Predict the address of a contract instantiated by `sender` via `CREATE` with `nonce`.
+	pub fn predict_create_address(sender: &H160, nonce: u64) -> H160 {
+		address::create1(sender, nonce)
+	}
+
+	/// Predict the address of a contract instantiated by `sender` via `CREATE2`.
+	///
+	/// `init_code_hash` is the keccak-256 hash of the init code including the constructor input.
+	pub fn predict_create2_address(
+		sender: &H160,
+		salt: &[u8; 32],
+		init_code_hash: &[u8; 32],
+	) -> H160 {
+		address::create2_from_init_code_hash(sender, salt, init_code_hash)
+	}
+
 	/// Remove the code from storage and refund the deposit to its owner.
 	///
 	/// Applies all necessary checks before removing the code.
@@ -346,7 +363,12 @@ impl<T: Config> Executable<T> for ContractBlob<T> {
 			prepared_call.call()
 		} else if T::AllowEVMBytecode::get() {
 			use revm::bytecode::Bytecode;
-			let bytecode = Bytecode::new_raw(self.code.into());
+			let bytecode = if function == ExportedFunction::Call {
+				ext.evm_bytecode(self.code_hash, || self.code)
+			} else {
+				// Init code is not stored on-chain and has no code hash to cache it by.
+				Bytecode::new_raw(self.code.into())
+			};
 			evm::call(bytecode, ext, input_data)
 		} else {
 			Err(Error::<T>::CodeRejected.into())
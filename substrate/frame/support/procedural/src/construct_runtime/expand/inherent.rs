@@ -60,6 +60,18 @@ pub fn expand_outer_inherent(
 				&self,
 				block: &<#block as #scrate::sp_runtime::traits::Block>::LazyBlock
 			) -> #scrate::inherent::CheckInherentsResult;
+			/// Same as `check_extrinsics`, but also checks that the inherents of the block respect
+			/// `ordering`.
+			///
+			/// Each `(before, after)` pair declares that no inherent identified by `after` may be
+			/// placed in front of an inherent identified by `before`. A violation is reported as
+			/// a fatal `InherentOrderingError` under `INHERENT_ORDERING_IDENTIFIER`, so it can be
+			/// told apart from a missing or invalid inherent.
+			fn check_extrinsics_with_ordering(
+				&self,
+				block: &<#block as #scrate::sp_runtime::traits::Block>::LazyBlock,
+				ordering: &[(#scrate::inherent::InherentIdentifier, #scrate::inherent::InherentIdentifier)],
+			) -> #scrate::inherent::CheckInherentsResult;
 		}
 
 		impl InherentDataExt for #scrate::inherent::InherentData {
@@ -191,6 +203,71 @@ pub fn expand_outer_inherent(
 
 				result
 			}
+
+			fn check_extrinsics_with_ordering(
+				&self,
+				block: &<#block as #scrate::sp_runtime::traits::Block>::LazyBlock,
+				ordering: &[(#scrate::inherent::InherentIdentifier, #scrate::inherent::InherentIdentifier)],
+			) -> #scrate::inherent::CheckInherentsResult {
+				use #scrate::inherent::{
+					InherentOrderingError, ProvideInherent, INHERENT_ORDERING_IDENTIFIER,
+				};
+				use #scrate::traits::IsSubType;
+				use #scrate::sp_runtime::traits::{ExtrinsicCall, LazyBlock};
+				use #scrate::__private::log;
+
+				let mut result = self.check_extrinsics(block);
+				if result.fatal_error() {
+					return result;
+				}
+
+				// The identifiers of the inherents, in the order they appear in the block.
+				let mut identifiers = #scrate::__private::Vec::new();
+				for maybe_xt in block.extrinsics() {
+					let Ok(xt) = maybe_xt else {
+						panic!("check_extrinsics_with_ordering(): Unable to decode extrinsic");
+					};
+
+					if !(#scrate::sp_runtime::traits::ExtrinsicLike::is_bare(&xt)) {
+						break
+					}
+
+					let mut identifier = None;
+					let call = ExtrinsicCall::call(&xt);
+					#(
+						#pallet_attrs
+						{
+							if let Some(call) = IsSubType::<_>::is_sub_type(call) {
+								if #pallet_names::is_inherent(call) {
+									identifier = Some(#pallet_names::INHERENT_IDENTIFIER);
+								}
+							}
+						}
+					)*
+
+					let Some(identifier) = identifier else { break };
+					identifiers.push(identifier);
+				}
+
+				for (before, after) in ordering {
+					let first_after = identifiers.iter().position(|id| id == after);
+					let last_before = identifiers.iter().rposition(|id| id == before);
+					if let (Some(first_after), Some(last_before)) = (first_after, last_before) {
+						if first_after < last_before {
+							let error = InherentOrderingError { before: *before, after: *after };
+							if result.put_error(INHERENT_ORDERING_IDENTIFIER, &error).is_err() {
+								log::error!(
+									target: "runtime::inherent",
+									"Unexpected error from `put_error` operation",
+								);
+							}
+							return result;
+						}
+					}
+				}
+
+				result
+			}
 		}
 
 		impl #scrate::traits::IsInherent<<#block as #scrate::sp_runtime::traits::Block>::Extrinsic> for #runtime {
@@ -19,6 +19,27 @@ pub use sp_inherents::{
 	CheckInherentsResult, InherentData, InherentIdentifier, IsFatalError, MakeFatalError,
 };
 
+/// The identifier under which [`InherentOrderingError`] is reported.
+pub const INHERENT_ORDERING_IDENTIFIER: InherentIdentifier = *b"inhorder";
+
+/// The inherents of a block do not respect the ordering declared by the runtime.
+///
+/// Reported by the `check_extrinsics_with_ordering` function generated by `construct_runtime!`
+/// under [`INHERENT_ORDERING_IDENTIFIER`]. This is always a fatal error.
+#[derive(codec::Encode, codec::Decode, Clone, PartialEq, Eq, Debug)]
+pub struct InherentOrderingError {
+	/// The identifier of the inherent that must come first.
+	pub before: InherentIdentifier,
+	/// The identifier of the inherent that was found in front of `before`.
+	pub after: InherentIdentifier,
+}
+
+impl IsFatalError for InherentOrderingError {
+	fn is_fatal_error(&self) -> bool {
+		true
+	}
+}
+
 /// A pallet that provides or verifies an inherent extrinsic will implement this trait.
 ///
 /// The pallet may provide an inherent, verify an inherent, or both provide and verify.
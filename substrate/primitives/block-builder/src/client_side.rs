@@ -19,6 +19,28 @@ use crate::BlockBuilder;
 
 use sp_inherents::{InherentData, InherentDataProvider, InherentIdentifier};
 use sp_runtime::traits::Block as BlockT;
+use std::time::Duration;
+
+/// Time spent in the individual phases of building a block.
+///
+/// Lets the block author see where the block building time goes, e.g. to compare it against
+/// the time budget for building a block.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BlockBuildingTimings {
+	/// Time spent in `inherent_extrinsics` creating the inherents.
+	pub inherents: Duration,
+	/// Time spent in `apply_extrinsic`, summed up over all applied extrinsics.
+	pub extrinsics: Duration,
+	/// Time spent in `finalize_block`.
+	pub finalize: Duration,
+}
+
+impl BlockBuildingTimings {
+	/// The time spent in all phases together.
+	pub fn total(&self) -> Duration {
+		self.inherents + self.extrinsics + self.finalize
+	}
+}
 
 /// Errors that occur when creating and checking on the client side.
 #[derive(Debug)]
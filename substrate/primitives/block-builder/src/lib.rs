@@ -29,6 +29,7 @@ pub use client_side::*;
 
 use sp_inherents::{CheckInherentsResult, InherentData};
 use sp_runtime::{traits::Block as BlockT, ApplyExtrinsicResult};
+use sp_weights::WeightReport;
 
 sp_api::decl_runtime_apis! {
 	/// The `BlockBuilder` api trait that provides the required functionality for building a block.
@@ -56,5 +57,52 @@ sp_api::decl_runtime_apis! {
 
 		/// Check that the inherents are valid. The inherent data will vary from chain to chain.
 		fn check_inherents(block: <Block as BlockT>::LazyBlock, data: InherentData) -> CheckInherentsResult;
+
+		/// Generate the inherent extrinsics for `data` and apply them in one call.
+		///
+		/// Returns the inherent errors, without applying anything, if the generated inherents
+		/// fail the inherent checks. Otherwise returns each inherent together with the result of
+		/// applying it. Only the inherents that applied successfully are part of the block.
+		#[api_version(7)]
+		fn apply_inherents(
+			data: InherentData,
+		) -> Result<
+			alloc::vec::Vec<(<Block as BlockT>::Extrinsic, ApplyExtrinsicResult)>,
+			CheckInherentsResult,
+		>;
+
+		/// Generate the inherent extrinsics for `data` and check them against the same `data`.
+		///
+		/// Returns the generated inherents together with the result of checking exactly these.
+		#[api_version(7)]
+		fn generate_and_check_inherents(
+			data: InherentData,
+		) -> (alloc::vec::Vec<<Block as BlockT>::Extrinsic>, CheckInherentsResult);
+
+		/// Apply each of the given extrinsics on top of the current state, independently of each
+		/// other.
+		///
+		/// The changes of every extrinsic are discarded before the next one is applied, so each
+		/// result reflects only the state the call was made at.
+		#[api_version(7)]
+		fn dry_run_extrinsics(
+			extrinsics: alloc::vec::Vec<<Block as BlockT>::Extrinsic>,
+		) -> alloc::vec::Vec<ApplyExtrinsicResult>;
+
+		/// Apply the given SCALE encoded extrinsic.
+		///
+		/// Same as [`Self::apply_extrinsic`], but an extrinsic that fails to decode is reported as
+		/// an invalid transaction instead of failing the runtime call.
+		#[api_version(7)]
+		fn try_apply_extrinsic(extrinsic: alloc::vec::Vec<u8>) -> ApplyExtrinsicResult;
+
+		/// Apply the given extrinsic and report its weight.
+		///
+		/// Same as [`Self::apply_extrinsic`], but additionally returns the weight the extrinsic
+		/// announced before dispatch and the weight it reported after dispatch.
+		#[api_version(7)]
+		fn apply_extrinsic_with_weight_report(
+			extrinsic: <Block as BlockT>::Extrinsic,
+		) -> (ApplyExtrinsicResult, WeightReport);
 	}
 }
@@ -75,6 +75,22 @@ impl RuntimeDbWeight {
 	}
 }
 
+/// The weight an extrinsic announced before dispatch and the weight it reported after dispatch.
+#[derive(Clone, Copy, Eq, PartialEq, Default, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct WeightReport {
+	/// The total pre-dispatch weight, including the weight of the transaction extensions.
+	pub announced: Weight,
+	/// The post-dispatch weight as reported by the extrinsic, not capped at `announced`.
+	pub actual: Weight,
+}
+
+impl WeightReport {
+	/// Returns by how much `actual` exceeds `announced`, per weight component.
+	pub fn overshoot(&self) -> Weight {
+		self.actual.saturating_sub(self.announced)
+	}
+}
+
 /// One coefficient and its position in the `WeightToFee`.
 ///
 /// One term of polynomial is calculated as:
@@ -550,6 +550,7 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(7)]
 	impl sp_block_builder::BlockBuilder<Block> for Runtime {
 		fn apply_extrinsic(extrinsic: <Block as BlockT>::Extrinsic) -> ApplyExtrinsicResult {
 			Executive::apply_extrinsic(extrinsic)
@@ -567,6 +568,34 @@ impl_runtime_apis! {
 		fn check_inherents(_block: <Block as BlockT>::LazyBlock, _data: InherentData) -> CheckInherentsResult {
 			CheckInherentsResult::new()
 		}
+
+		fn apply_inherents(
+			data: InherentData,
+		) -> Result<Vec<(<Block as BlockT>::Extrinsic, ApplyExtrinsicResult)>, CheckInherentsResult> {
+			Executive::apply_inherents(data, |_| vec![], |_, _| CheckInherentsResult::new())
+		}
+
+		fn generate_and_check_inherents(
+			data: InherentData,
+		) -> (Vec<<Block as BlockT>::Extrinsic>, CheckInherentsResult) {
+			Executive::generate_and_check_inherents(data, |_| vec![], |_, _| CheckInherentsResult::new())
+		}
+
+		fn dry_run_extrinsics(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+		) -> Vec<ApplyExtrinsicResult> {
+			Executive::dry_run_extrinsics(extrinsics)
+		}
+
+		fn apply_extrinsic_with_weight_report(
+			extrinsic: <Block as BlockT>::Extrinsic,
+		) -> (ApplyExtrinsicResult, frame_support::weights::WeightReport) {
+			Executive::apply_extrinsic_with_weight_report(extrinsic)
+		}
+
+		fn try_apply_extrinsic(extrinsic: Vec<u8>) -> ApplyExtrinsicResult {
+			Executive::try_apply_extrinsic(&extrinsic)
+		}
 	}
 
 	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {